@@ -0,0 +1,42 @@
+//! A game staked in a token the contract escrows itself, with no hub involved:
+//! both players deposit on start and the winner is paid the whole pot.
+//!
+//!     cargo run --example escrow_game --features testutils
+
+use hand_cricket::testutils::script::{GameScript, Step::*, Who::*};
+use hand_cricket::testutils::setup;
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::token::{StellarAssetClient, TokenClient};
+use soroban_sdk::Address;
+
+fn run() -> [(i128, i128, i128); 2] {
+    let t = setup();
+    let token = t.env.register_stellar_asset_contract_v2(Address::generate(&t.env)).address();
+    let asset = StellarAssetClient::new(&t.env, &token);
+    asset.mint(&t.player1, &1_000);
+    asset.mint(&t.player2, &1_000);
+    let token = TokenClient::new(&t.env, &token);
+    let balances = || (token.balance(&t.player1), token.balance(&t.player2), token.balance(&t.client.address));
+
+    t.client.start_game_with_token(&4, &t.player1, &t.player2, &token.address, &300);
+    let staked = balances();
+    println!("staked: player1 {} player2 {} escrow {}", staked.0, staked.1, staked.2);
+
+    // Player1 sets 9 and bowls player2 out for 5
+    let game = GameScript::attach(&t, 4).run(&[TossWonBy(P1), ChooseBat(P1), Ball(4, 1), Ball(5, 2), Ball(3, 3), Ball(1, 5), Ball(4, 4)]);
+    let paid = balances();
+    println!("finished: player1 {} player2 {}; paid: player1 {} player2 {} escrow {}", game.p1_score, game.p2_score, paid.0, paid.1, paid.2);
+    println!("hub end_game calls: {}", t.hub.get_end_calls().len());
+    [staked, paid]
+}
+
+fn main() {
+    run();
+}
+
+#[test]
+fn escrow_game_example() {
+    let [staked, paid] = run();
+    assert_eq!(staked, (700, 700, 600));
+    assert_eq!(paid, (1_300, 700, 0));
+}
@@ -0,0 +1,40 @@
+//! Starts a game with uneven stakes and follows them through the hub: the
+//! points each player puts up are reported on start, and the result on end.
+//! The contract holds no funds for a hub game; settling the points is the
+//! hub's job. For stakes the contract escrows itself, see `escrow_game`.
+//!
+//!     cargo run --example hub_stakes --features testutils
+
+use hand_cricket::testutils::script::{GameScript, Step::*, Who::*};
+use hand_cricket::testutils::{setup, EndCall, StartCall};
+
+fn run() -> (StartCall, EndCall) {
+    let t = setup();
+    let (p1_points, p2_points) = (250_0000000i128, 100_0000000i128);
+    t.client.start_game(&2, &t.player1, &t.player2, &p1_points, &p2_points);
+    let start = t.hub.get_start_calls().get(0).unwrap();
+    println!(
+        "hub start_game: session {} player1 stakes {} player2 stakes {}",
+        start.session_id, start.player1_points, start.player2_points
+    );
+
+    // Player2 bowls player1 out first ball, then scores the single run needed
+    let script = GameScript::attach(&t, 2);
+    let game = script.run(&[TossWonBy(P2), ChooseBowl(P2), Ball(4, 4), Ball(5, 1)]);
+    println!("finished: player1 {} player2 {} (target {})", game.p1_score, game.p2_score, game.target);
+
+    let end = t.hub.get_end_calls().get(0).unwrap();
+    println!("hub end_game: session {} player1_won {}", end.session_id, end.player1_won);
+    (start, end)
+}
+
+fn main() {
+    run();
+}
+
+#[test]
+fn hub_stakes_example() {
+    let (start, end) = run();
+    assert_eq!((start.player1_points, start.player2_points), (250_0000000, 100_0000000));
+    assert_eq!((end.session_id, end.player1_won), (2, false));
+}
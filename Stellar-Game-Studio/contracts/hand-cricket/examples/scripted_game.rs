@@ -0,0 +1,56 @@
+//! Plays a complete scripted game against the mock hub, printing every state
+//! transition.
+//!
+//!     cargo run --example scripted_game --features testutils
+
+use hand_cricket::testutils::script::{GameScript, Step, Step::*, Who::*};
+use hand_cricket::testutils::setup;
+use hand_cricket::Game;
+
+// Player1 bats first and sets 11; player2 chases it down.
+const STEPS: [Step; 7] = [
+    TossWonBy(P1),
+    ChooseBat(P1),
+    Ball(6, 2),
+    Ball(4, 1),
+    Ball(3, 3), // out on 10: target 11
+    Ball(1, 5),
+    Ball(2, 6), // player2 reaches 11
+];
+
+fn run() -> Game {
+    let t = setup();
+    let script = GameScript::start(&t, 1);
+    print_state("start", &script.game());
+    for step in STEPS {
+        script.step(step);
+        print_state(&format!("{:?}", step), &script.game());
+    }
+    let calls = t.hub.get_end_calls();
+    println!("hub end_game calls: {:?}", calls.iter().map(|c| (c.session_id, c.player1_won)).collect::<Vec<_>>());
+    script.game()
+}
+
+fn print_state(label: &str, game: &Game) {
+    println!(
+        "{:<14} phase {:<13} innings {} target {:>2}  p1 {:>2}  p2 {:>2}",
+        label,
+        format!("{:?}", game.phase),
+        game.innings,
+        game.target,
+        game.p1_score,
+        game.p2_score
+    );
+}
+
+fn main() {
+    run();
+}
+
+#[test]
+fn scripted_game_example() {
+    let game = run();
+    assert_eq!(game.phase, hand_cricket::Phase::Finished);
+    assert_eq!((game.p1_score, game.p2_score, game.target), (10, 11, 11));
+    assert_eq!(game.winner, Some(game.player2.clone()));
+}
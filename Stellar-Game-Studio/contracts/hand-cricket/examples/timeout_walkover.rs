@@ -0,0 +1,40 @@
+//! A player who stops playing forfeits: player2 never commits to the toss, so
+//! once the timeout passes player1 claims the game and the hub hears the win.
+//!
+//!     cargo run --example timeout_walkover --features testutils
+
+use hand_cricket::testutils::fixtures::{self, Preimage, Scheme};
+use hand_cricket::testutils::{advance_ledgers, setup, EndCall};
+use hand_cricket::{Error, GamePublicView};
+
+fn run() -> (Error, GamePublicView, EndCall) {
+    let t = setup();
+    t.client.start_game(&3, &t.player1, &t.player2, &100_0000000, &100_0000000);
+    let preimage = Preimage { number: 4, salt: fixtures::salt(1), session_id: 3, innings: 1, ball_index: 0, player: t.player1.clone() };
+    t.client.commit_number(&3, &t.player1, &fixtures::make_commitment(&t.env, Scheme::SaltedHash, &preimage));
+    println!("player1 committed to the toss; player2 goes quiet");
+
+    let early = t.client.try_claim_timeout(&3, &t.player1).unwrap_err().unwrap();
+    println!("claim straight away: {:?}", early);
+
+    let ledgers = t.client.get_timeout_ledgers();
+    advance_ledgers(&t.env, ledgers);
+    let view = t.client.claim_timeout(&3, &t.player1);
+    println!("claim after {} ledgers: phase {:?}, player1 wins {}", ledgers, view.phase, view.winner == Some(t.player1.clone()));
+
+    let end = t.hub.get_end_calls().get(0).unwrap();
+    println!("hub end_game: session {} player1_won {}", end.session_id, end.player1_won);
+    (early, view, end)
+}
+
+fn main() {
+    run();
+}
+
+#[test]
+fn timeout_walkover_example() {
+    let (early, view, end) = run();
+    assert_eq!(early, Error::TimeoutNotReached);
+    assert_eq!(view.phase, hand_cricket::Phase::Finished);
+    assert_eq!((end.session_id, end.player1_won), (3, true));
+}
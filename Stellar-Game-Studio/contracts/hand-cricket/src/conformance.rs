@@ -0,0 +1,214 @@
+//! Conformance vectors for client libraries.
+//!
+//! Enabled with the `testutils` feature (host-only, needs `std`). Every vector
+//! is produced by calling the same code the contract runs, so the checked-in
+//! `tests/vectors/conformance.json` cannot drift from the contract without a
+//! test failing. Regenerate it with `UPDATE_VECTORS=1 cargo test`.
+//!
+//! * `toss`: session id and player strkeys → `player1_is_odd`, plus the toss
+//!   winner for a few number pairs.
+//! * `commitments`: [`fixtures::Preimage`] → reveal context, commitment and
+//!   proof blob bytes, for every scheme in [`fixtures::Scheme`].
+//! * `balls`: innings, target, batter score and both numbers → [`BallOutcome`].
+
+extern crate std;
+
+use crate::testutils::fixtures::{self, Preimage, Scheme};
+use crate::{ball_outcome, toss_player1_is_odd, toss_player1_wins, BallOutcome};
+use soroban_sdk::{Address, Env};
+use std::format;
+use std::string::String;
+use std::vec::Vec;
+
+/// Fixed player addresses used by the vectors.
+pub const PLAYERS: [&str; 3] = [
+    "GAAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQDZ7H",
+    "GABAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEAQCAIBAEJXA",
+    "CABQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGAYDAMBQGCK3",
+];
+
+pub struct TossVector {
+    pub session_id: u32,
+    pub player1: &'static str,
+    pub player2: &'static str,
+    pub player1_is_odd: bool,
+    /// `(p1_number, p2_number, player1_wins)`
+    pub rounds: Vec<(u32, u32, bool)>,
+}
+
+pub struct CommitmentVector {
+    pub scheme: Scheme,
+    pub preimage: Preimage,
+    pub player: &'static str,
+    pub vk_version: u32,
+    pub context: [u8; 32],
+    pub commitment: [u8; 32],
+    pub proof_blob: Vec<u8>,
+}
+
+pub struct BallVector {
+    pub innings: u32,
+    pub target: u32,
+    pub batter_score: u32,
+    pub batter_number: u32,
+    pub bowler_number: u32,
+    pub outcome: BallOutcome,
+}
+
+pub fn toss_vectors(env: &Env) -> Vec<TossVector> {
+    let pairs = [(0, 1), (1, 0), (1, 2), (0, 2), (2, 1), (2, 2)];
+    [0u32, 1, 2, 42, 1_000_000, u32::MAX]
+        .into_iter()
+        .enumerate()
+        .map(|(i, session_id)| {
+            let player1 = PLAYERS[i % PLAYERS.len()];
+            let player2 = PLAYERS[(i + 1) % PLAYERS.len()];
+            let player1_is_odd = toss_player1_is_odd(
+                env,
+                session_id,
+                &Address::from_str(env, player1),
+                &Address::from_str(env, player2),
+            );
+            let (a, b) = pairs[i];
+            let rounds = [(a, b), (b + 3, a + 6)]
+                .into_iter()
+                .map(|(n1, n2)| (n1, n2, toss_player1_wins(player1_is_odd, n1, n2)))
+                .collect();
+            TossVector { session_id, player1, player2, player1_is_odd, rounds }
+        })
+        .collect()
+}
+
+pub fn commitment_vectors(env: &Env) -> Vec<CommitmentVector> {
+    let salts = [fixtures::salt(0), fixtures::salt(1), fixtures::salt(u32::MAX), [0xab; 32]];
+    let mut vectors = Vec::new();
+    for scheme in [Scheme::LegacyBlob, Scheme::SaltedHash] {
+        for (i, number) in [0u32, 1, 2, 3, 4, 5, 6, u32::MAX].into_iter().enumerate() {
+            let player = PLAYERS[i % 2];
+            let preimage = Preimage {
+                number,
+                salt: salts[i % salts.len()],
+                session_id: i as u32 * 7,
+                innings: 1 + i as u32 % 2,
+                ball_index: i as u32,
+                player: Address::from_str(env, player),
+            };
+            let blob = fixtures::make_proof_blob(env, scheme, 1, &preimage);
+            let mut proof_blob = std::vec![0u8; blob.len() as usize];
+            blob.copy_into_slice(&mut proof_blob);
+            vectors.push(CommitmentVector {
+                scheme,
+                context: fixtures::make_context(env, &preimage).to_array(),
+                commitment: fixtures::make_commitment(env, scheme, &preimage).to_array(),
+                preimage,
+                player,
+                vk_version: 1,
+                proof_blob,
+            });
+        }
+    }
+    vectors
+}
+
+pub fn ball_vectors() -> Vec<BallVector> {
+    // (innings, target, batter_score, batter_number, bowler_number)
+    let cases = [
+        (1, 0, 0, 4, 2),   // first ball scores
+        (1, 0, 12, 6, 1),  // runs accumulate
+        (1, 0, 0, 3, 3),   // first-ball wicket: target 1
+        (1, 0, 17, 5, 5),  // innings break
+        (2, 18, 0, 2, 2),  // second innings duck
+        (2, 18, 10, 4, 1), // chase continues
+        (2, 18, 14, 4, 6), // chase ends on the exact target
+        (2, 18, 15, 6, 1), // chase passes the target
+        (2, 18, 17, 1, 1), // out one run short: tie
+        (2, 18, 16, 1, 1), // out two runs short
+        (2, 1, 0, 1, 2),   // chasing a target of one
+        (2, 5, 3, 1, 4),   // one short, not out
+        (1, 0, 0, 0, 0),   // zero against zero is still out
+    ];
+    cases
+        .into_iter()
+        .map(|(innings, target, batter_score, batter_number, bowler_number)| BallVector {
+            innings,
+            target,
+            batter_score,
+            batter_number,
+            bowler_number,
+            outcome: ball_outcome(innings, target, batter_score, batter_number, bowler_number),
+        })
+        .collect()
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn scheme_name(scheme: Scheme) -> &'static str {
+    match scheme {
+        Scheme::LegacyBlob => "legacy_blob",
+        Scheme::SaltedHash => "salted_hash",
+    }
+}
+
+impl TossVector {
+    pub fn to_json(&self) -> String {
+        let rounds: Vec<String> = self
+            .rounds
+            .iter()
+            .map(|(n1, n2, wins)| format!("{{\"p1_number\": {}, \"p2_number\": {}, \"player1_wins\": {}}}", n1, n2, wins))
+            .collect();
+        format!(
+            "{{\"session_id\": {}, \"player1\": \"{}\", \"player2\": \"{}\", \"player1_is_odd\": {}, \"rounds\": [{}]}}",
+            self.session_id,
+            self.player1,
+            self.player2,
+            self.player1_is_odd,
+            rounds.join(", ")
+        )
+    }
+}
+
+impl CommitmentVector {
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"scheme\": \"{}\", \"number\": {}, \"salt\": \"{}\", \"session_id\": {}, \"innings\": {}, \"ball_index\": {}, \"player\": \"{}\", \"vk_version\": {}, \"context\": \"{}\", \"commitment\": \"{}\", \"proof_blob\": \"{}\"}}",
+            scheme_name(self.scheme),
+            self.preimage.number,
+            hex(&self.preimage.salt),
+            self.preimage.session_id,
+            self.preimage.innings,
+            self.preimage.ball_index,
+            self.player,
+            self.vk_version,
+            hex(&self.context),
+            hex(&self.commitment),
+            hex(&self.proof_blob)
+        )
+    }
+}
+
+impl BallVector {
+    pub fn to_json(&self) -> String {
+        let outcome = match self.outcome {
+            BallOutcome::Runs(score) => format!("{{\"kind\": \"runs\", \"score\": {}}}", score),
+            BallOutcome::InningsBreak { target } => format!("{{\"kind\": \"innings_break\", \"target\": {}}}", target),
+            BallOutcome::ChaseComplete(score) => format!("{{\"kind\": \"chase_complete\", \"score\": {}}}", score),
+            BallOutcome::ChaseFailed => String::from("{\"kind\": \"chase_failed\"}"),
+            BallOutcome::ChaseTied => String::from("{\"kind\": \"chase_tied\"}"),
+        };
+        format!(
+            "{{\"innings\": {}, \"target\": {}, \"batter_score\": {}, \"batter_number\": {}, \"bowler_number\": {}, \"outcome\": {}}}",
+            self.innings, self.target, self.batter_score, self.batter_number, self.bowler_number, outcome
+        )
+    }
+}
+
+/// The full vectors document, one vector per line so diffs stay readable.
+pub fn to_json(env: &Env) -> String {
+    let section = |name: &str, items: Vec<String>| format!("  \"{}\": [\n    {}\n  ]", name, items.join(",\n    "));
+    let toss = section("toss", toss_vectors(env).iter().map(TossVector::to_json).collect());
+    let commitments = section("commitments", commitment_vectors(env).iter().map(CommitmentVector::to_json).collect());
+    let balls = section("balls", ball_vectors().iter().map(BallVector::to_json).collect());
+    format!("{{\n{},\n{},\n{}\n}}\n", toss, commitments, balls)
+}
@@ -0,0 +1,62 @@
+//! Feature bits reported by `get_features`, shared with the hub and clients.
+//!
+//! Bits are never reused: a feature added later claims the next free bit and
+//! a name in [`bit_for`].
+//!
+//! [`FEATURES`] follows the cargo features the wasm was built with, so a
+//! minimal build reports only what it actually contains. The operator can
+//! switch [`TOGGLEABLE`] features off at runtime; `get_enabled_features`
+//! reports what is currently on. A toggleable feature added by an upgrade
+//! starts switched off on deployments that have already stored toggles.
+
+use soroban_sdk::{symbol_short, Symbol};
+
+/// Reveals are checked against a proof blob.
+pub const PROOF_MODE: u32 = 1 << 0;
+/// Several verifying key versions can be active, with games pinned to one.
+pub const VK_VERSIONING: u32 = 1 << 1;
+/// `debug_dump` diagnostic view.
+pub const DEBUG_DUMP: u32 = 1 << 2;
+/// Stakes escrowed by the contract itself; see `start_game_with_token`. Switched off, no new escrowed game
+/// starts; those under way still pay out.
+pub const ESCROW: u32 = 1 << 3;
+/// Best-of-N series; see `start_series`. Switched off, no new series start; those under way play out.
+pub const TOURNAMENTS: u32 = 1 << 4;
+/// `claim_timeout` against a stalling player. Part of the core game, so always built in.
+pub const TIMEOUTS: u32 = 1 << 5;
+/// Lifetime player statistics, head-to-head records and the leaderboard. Switched off, finished games
+/// go unrecorded.
+pub const STATS: u32 = 1 << 6;
+/// Games against the contract's bot; see `start_practice_game`. Switched off, no new practice game starts;
+/// those under way play out.
+pub const PRACTICE: u32 = 1 << 7;
+
+// Built in with the `zk` cargo feature.
+const ZK: u32 = if cfg!(feature = "zk") { PROOF_MODE | VK_VERSIONING } else { 0 };
+// Built in with the `escrow` cargo feature.
+const ESCROW_BUILT: u32 = if cfg!(feature = "escrow") { ESCROW } else { 0 };
+// Built in with the `tournaments` cargo feature.
+const TOURNAMENTS_BUILT: u32 = if cfg!(feature = "tournaments") { TOURNAMENTS } else { 0 };
+// Built in with the `stats` cargo feature.
+const STATS_BUILT: u32 = if cfg!(feature = "stats") { STATS } else { 0 };
+
+/// Everything this build supports.
+pub const FEATURES: u32 = DEBUG_DUMP | TIMEOUTS | PRACTICE | ZK | ESCROW_BUILT | TOURNAMENTS_BUILT | STATS_BUILT;
+
+/// Features the operator may switch off. Core gameplay bits are never toggleable.
+pub const TOGGLEABLE: u32 = DEBUG_DUMP | ESCROW | TOURNAMENTS | STATS | PRACTICE;
+
+/// The bit a feature name maps to, if the name is known.
+pub fn bit_for(feature: &Symbol) -> Option<u32> {
+    let names = [
+        (symbol_short!("proof"), PROOF_MODE),
+        (symbol_short!("vk_vers"), VK_VERSIONING),
+        (symbol_short!("debug"), DEBUG_DUMP),
+        (symbol_short!("escrow"), ESCROW),
+        (symbol_short!("tourney"), TOURNAMENTS),
+        (symbol_short!("timeouts"), TIMEOUTS),
+        (symbol_short!("stats"), STATS),
+        (symbol_short!("practice"), PRACTICE),
+    ];
+    names.into_iter().find(|(name, _)| name == feature).map(|(_, bit)| bit)
+}
@@ -0,0 +1,243 @@
+//! Groth16 verification over BLS12-381 for reveal proofs.
+//!
+//! Layouts (points uncompressed, as the host encodes them):
+//!
+//! * Verifying key, [`VK_LEN`] bytes: `[alpha G1][beta G2][gamma G2][delta G2][ic0 G1]..[ic3 G1]`.
+//! * Proof, [`PROOF_LEN`] bytes: `[a G1][b G2][c G1]`.
+//!
+//! The three public inputs are the commitment, the revealed number and the
+//! reveal context (`crate::reveal_context`), the 32-byte values read as
+//! big-endian integers reduced into the scalar field. A proof verifies when
+//! `e(a, b) = e(alpha, beta) · e(ic, gamma) · e(c, delta)` with
+//! `ic = ic0 + commitment · ic1 + number · ic2 + context · ic3`.
+//!
+//! The host traps on points that are off the curve or outside the subgroup,
+//! so every point is checked here first and a bad one fails verification
+//! instead of aborting the call.
+
+use soroban_sdk::crypto::bls12_381::{Fr, G1Affine, G2Affine, G1_SERIALIZED_SIZE, G2_SERIALIZED_SIZE};
+use soroban_sdk::{vec, Bytes, BytesN, Env, U256};
+
+const G1_LEN: u32 = G1_SERIALIZED_SIZE as u32;
+const G2_LEN: u32 = G2_SERIALIZED_SIZE as u32;
+const IC_COUNT: u32 = 4;
+pub const VK_LEN: u32 = G1_LEN + 3 * G2_LEN + IC_COUNT * G1_LEN;
+pub const PROOF_LEN: u32 = 2 * G1_LEN + G2_LEN;
+
+/// Keys of any other length are opaque keys from before Groth16 verification.
+pub fn is_groth16_key(key: &Bytes) -> bool {
+    key.len() == VK_LEN
+}
+
+/// Every point in `key` is well formed and in its subgroup.
+pub fn validate_verifying_key(env: &Env, key: &Bytes) -> bool {
+    parse_verifying_key(env, key).is_some()
+}
+
+pub fn verify(env: &Env, key: &Bytes, commitment: &BytesN<32>, number: u32, context: &BytesN<32>, proof: &Bytes) -> bool {
+    let Some(vk) = parse_verifying_key(env, key) else { return false };
+    if proof.len() != PROOF_LEN { return false; }
+    let (Some(a), Some(b), Some(c)) = (
+        g1_at(env, proof, 0),
+        g2_at(env, proof, G1_LEN),
+        g1_at(env, proof, G1_LEN + G2_LEN),
+    ) else {
+        return false;
+    };
+    let bls = env.crypto().bls12_381();
+    let inputs = vec![
+        env,
+        Fr::from_u256(U256::from_u32(env, 1)),
+        Fr::from_bytes(commitment.clone()),
+        Fr::from_u256(U256::from_u32(env, number)),
+        Fr::from_bytes(context.clone()),
+    ];
+    let ic = bls.g1_msm(vk.ic, inputs);
+    bls.pairing_check(vec![env, -a, vk.alpha, ic, c], vec![env, b, vk.beta, vk.gamma, vk.delta])
+}
+
+struct VerifyingKey {
+    alpha: G1Affine,
+    beta: G2Affine,
+    gamma: G2Affine,
+    delta: G2Affine,
+    ic: soroban_sdk::Vec<G1Affine>,
+}
+
+fn parse_verifying_key(env: &Env, key: &Bytes) -> Option<VerifyingKey> {
+    if !is_groth16_key(key) { return None; }
+    let mut ic = soroban_sdk::Vec::new(env);
+    for i in 0..IC_COUNT {
+        ic.push_back(g1_at(env, key, G1_LEN + 3 * G2_LEN + i * G1_LEN)?);
+    }
+    Some(VerifyingKey {
+        alpha: g1_at(env, key, 0)?,
+        beta: g2_at(env, key, G1_LEN)?,
+        gamma: g2_at(env, key, G1_LEN + G2_LEN)?,
+        delta: g2_at(env, key, G1_LEN + 2 * G2_LEN)?,
+        ic,
+    })
+}
+
+fn g1_at(env: &Env, bytes: &Bytes, offset: u32) -> Option<G1Affine> {
+    let mut raw = [0u8; G1_SERIALIZED_SIZE];
+    bytes.slice(offset..offset + G1_LEN).copy_into_slice(&mut raw);
+    if !infinity_or_unflagged(&raw)? {
+        let x = fp::from_be(&raw[0..48])?;
+        let y = fp::from_be(&raw[48..96])?;
+        if !fp::g1_on_curve(&x, &y) { return None; }
+    }
+    let point = G1Affine::from_array(env, &raw);
+    env.crypto().bls12_381().g1_is_in_subgroup(&point).then_some(point)
+}
+
+fn g2_at(env: &Env, bytes: &Bytes, offset: u32) -> Option<G2Affine> {
+    let mut raw = [0u8; G2_SERIALIZED_SIZE];
+    bytes.slice(offset..offset + G2_LEN).copy_into_slice(&mut raw);
+    if !infinity_or_unflagged(&raw)? {
+        // Each coordinate is c1 || c0.
+        let x = (fp::from_be(&raw[48..96])?, fp::from_be(&raw[0..48])?);
+        let y = (fp::from_be(&raw[144..192])?, fp::from_be(&raw[96..144])?);
+        if !fp::g2_on_curve(&x, &y) { return None; }
+    }
+    let point = G2Affine::from_array(env, &raw);
+    env.crypto().bls12_381().g2_is_in_subgroup(&point).then_some(point)
+}
+
+// `Some(true)` for the point at infinity, `Some(false)` for a point with no flags set, `None` for
+// any other flag combination (compressed, sorted, or infinity with stray bits).
+fn infinity_or_unflagged(raw: &[u8]) -> Option<bool> {
+    match raw[0] & 0b1110_0000 {
+        0b0000_0000 => Some(false),
+        0b0100_0000 => (raw[0] == 0b0100_0000 && raw[1..].iter().all(|b| *b == 0)).then_some(true),
+        _ => None,
+    }
+}
+
+// Base field arithmetic, just enough to test the curve equations. Elements are six little-endian
+// u64 limbs in Montgomery form (R = 2^384).
+mod fp {
+    pub type Fp = [u64; 6];
+    pub type Fp2 = (Fp, Fp);
+
+    const P: Fp = [
+        0xb9fe_ffff_ffff_aaab, 0x1eab_fffe_b153_ffff, 0x6730_d2a0_f6b0_f624,
+        0x6477_4b84_f385_12bf, 0x4b1b_a7b6_434b_acd7, 0x1a01_11ea_397f_e69a,
+    ];
+    // -P^-1 mod 2^64
+    const INV: u64 = 0x89f3_fffc_fffc_fffd;
+    // R^2 mod P, to bring integers into Montgomery form.
+    const R2: Fp = [
+        0xf4df_1f34_1c34_1746, 0x0a76_e6a6_09d1_04f1, 0x8de5_476c_4c95_b6d5,
+        0x67eb_88a9_939d_83c0, 0x9a79_3e85_b519_952d, 0x1198_8fe5_92ca_e3aa,
+    ];
+    // The curve constant 4, in Montgomery form. G1 is y^2 = x^3 + 4, G2 is y^2 = x^3 + 4(1 + u).
+    const FOUR: Fp = [
+        0xaa27_0000_000c_fff3, 0x53cc_0032_fc34_000a, 0x478f_e97a_6b0a_807f,
+        0xb1d3_7ebe_e6ba_24d7, 0x8ec9_733b_bf78_ab2f, 0x09d6_4551_3d83_de7e,
+    ];
+
+    /// A 48-byte big-endian coordinate in Montgomery form, or `None` if it is not below P.
+    pub fn from_be(bytes: &[u8]) -> Option<Fp> {
+        let mut limbs = [0u64; 6];
+        for (i, limb) in limbs.iter_mut().enumerate() {
+            let start = 40 - 8 * i;
+            let mut word = [0u8; 8];
+            word.copy_from_slice(&bytes[start..start + 8]);
+            *limb = u64::from_be_bytes(word);
+        }
+        if !less_than_p(&limbs) { return None; }
+        Some(mul(&limbs, &R2))
+    }
+
+    pub fn g1_on_curve(x: &Fp, y: &Fp) -> bool {
+        mul(y, y) == add(&mul(&mul(x, x), x), &FOUR)
+    }
+
+    pub fn g2_on_curve(x: &Fp2, y: &Fp2) -> bool {
+        let rhs = fp2_mul(&fp2_mul(x, x), x);
+        fp2_mul(y, y) == (add(&rhs.0, &FOUR), add(&rhs.1, &FOUR))
+    }
+
+    // (a0 + a1 u)(b0 + b1 u) with u^2 = -1
+    fn fp2_mul(a: &Fp2, b: &Fp2) -> Fp2 {
+        (sub(&mul(&a.0, &b.0), &mul(&a.1, &b.1)), add(&mul(&a.0, &b.1), &mul(&a.1, &b.0)))
+    }
+
+    fn less_than_p(a: &Fp) -> bool {
+        for i in (0..6).rev() {
+            if a[i] != P[i] { return a[i] < P[i]; }
+        }
+        false
+    }
+
+    fn sub_p_if_needed(a: Fp) -> Fp {
+        if less_than_p(&a) { a } else { sub_raw(&a, &P).0 }
+    }
+
+    fn sub_raw(a: &Fp, b: &Fp) -> (Fp, bool) {
+        let mut out = [0u64; 6];
+        let mut borrow = false;
+        for i in 0..6 {
+            let (d, b1) = a[i].overflowing_sub(b[i]);
+            let (d, b2) = d.overflowing_sub(borrow as u64);
+            out[i] = d;
+            borrow = b1 || b2;
+        }
+        (out, borrow)
+    }
+
+    // P < 2^381, so the sum of two reduced elements never overflows six limbs.
+    fn add(a: &Fp, b: &Fp) -> Fp {
+        let mut out = [0u64; 6];
+        let mut carry = 0u64;
+        for i in 0..6 {
+            let t = a[i] as u128 + b[i] as u128 + carry as u128;
+            out[i] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        sub_p_if_needed(out)
+    }
+
+    fn sub(a: &Fp, b: &Fp) -> Fp {
+        let (d, borrow) = sub_raw(a, b);
+        if !borrow { return d; }
+        let mut out = [0u64; 6];
+        let mut carry = 0u64;
+        for i in 0..6 {
+            let t = d[i] as u128 + P[i] as u128 + carry as u128;
+            out[i] = t as u64;
+            carry = (t >> 64) as u64;
+        }
+        out
+    }
+
+    // Montgomery product a * b / R mod P (CIOS).
+    fn mul(a: &Fp, b: &Fp) -> Fp {
+        let mut t = [0u64; 8];
+        for bi in b.iter() {
+            let mut carry = 0u64;
+            for j in 0..6 {
+                (t[j], carry) = mac(t[j], a[j], *bi, carry);
+            }
+            let s = t[6] as u128 + carry as u128;
+            t[6] = s as u64;
+            t[7] = (s >> 64) as u64;
+
+            let m = t[0].wrapping_mul(INV);
+            let (_, mut carry) = mac(t[0], m, P[0], 0);
+            for j in 1..6 {
+                (t[j - 1], carry) = mac(t[j], m, P[j], carry);
+            }
+            let s = t[6] as u128 + carry as u128;
+            t[5] = s as u64;
+            t[6] = t[7] + (s >> 64) as u64;
+        }
+        sub_p_if_needed([t[0], t[1], t[2], t[3], t[4], t[5]])
+    }
+
+    fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+        let t = acc as u128 + a as u128 * b as u128 + carry as u128;
+        (t as u64, (t >> 64) as u64)
+    }
+}
@@ -0,0 +1,35 @@
+//! Read interface other contracts call to check hand-cricket results.
+//!
+//! The function signatures and [`ResultAttestation`] layout are stable:
+//! changing them breaks deployed consumers, so they only ever gain new
+//! functions. A consumer generates a client from [`HandCricketResults`] and
+//! points it at a hand-cricket deployment:
+//!
+//! ```ignore
+//! let results = HandCricketResultsClient::new(&env, &hand_cricket);
+//! match results.try_attest_result(&session_id) {
+//!     Ok(Ok(attestation)) => attestation.winner == player,
+//!     _ => false, // unknown, unfinished, drawn or unreadable
+//! }
+//! ```
+
+use crate::Error;
+use soroban_sdk::{contractclient, contracttype, Address, Env};
+
+/// The outcome of one finished session.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ResultAttestation {
+    pub player1: Address,
+    pub player2: Address,
+    pub winner: Address,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub finished_ledger: u32,
+}
+
+#[contractclient(name = "HandCricketResultsClient")]
+pub trait HandCricketResults {
+    /// `Error::GameNotFound` for unknown sessions, `Error::GameNotFinished` while one is still in play.
+    fn attest_result(env: Env, session_id: u32) -> Result<ResultAttestation, Error>;
+}
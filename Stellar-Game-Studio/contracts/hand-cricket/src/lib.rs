@@ -1,16 +1,30 @@
 #![no_std]
 
 use soroban_sdk::{
-    Address, Bytes, BytesN, Env, IntoVal, contract, contractclient,
-    contracterror, contractimpl, contracttype, vec,
+    Address, Bytes, BytesN, Env, IntoVal, Symbol, TryFromVal, Val, Vec, contract, contractclient,
+    contracterror, contractevent, contractimpl, contracttype, panic_with_error, symbol_short, vec, xdr::ToXdr,
 };
+#[cfg(feature = "escrow")]
+use soroban_sdk::token;
 
 #[contractclient(name = "GameHubClient")]
 pub trait GameHub {
     fn start_game(env: Env, game_id: Address, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128);
     fn end_game(env: Env, session_id: u32, player1_won: bool);
+    // `end_game` with the final scorecard; see `get_legacy_hub` for hubs that predate it.
+    fn end_game_detailed(env: Env, session_id: u32, player1_won: bool, p1_score: u32, p2_score: u32, target: u32, innings_played: u32);
+    fn end_game_draw(env: Env, session_id: u32);
+    fn cancel_game(env: Env, session_id: u32);
 }
 
+// External reveal check shared by several games; see `set_verifier`.
+#[contractclient(name = "VerifierClient")]
+pub trait Verifier {
+    // `context` is the `reveal_context` of the round being revealed.
+    fn verify(env: Env, commitment: BytesN<32>, number: u32, context: BytesN<32>, proof: Bytes) -> bool;
+}
+
+// Codes are fixed across feature sets; a build without a subsystem simply never returns its errors.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -25,6 +39,80 @@ pub enum Error {
     GameAlreadyEnded = 8,
     SelfPlay         = 9,
     NotTossWinner    = 10,
+    VerifyingKeyExists   = 11,
+    VerifyingKeyNotFound = 12,
+    DowngradeBlocked     = 13,
+    VersionMismatch      = 14,
+    Deprecated           = 15,
+    NotDeprecated        = 16,
+    LiveGames            = 17,
+    Retired              = 18,
+    NoRollbackTarget     = 19,
+    RollbackWindowClosed = 20,
+    SessionExists        = 21,
+    InvalidSnapshot      = 22,
+    HubMismatch          = 23,
+    FeatureDisabled      = 24,
+    FeatureNotToggleable = 25,
+    // WrongPhase narrowed to what the game is waiting for; see `get_expected_action`.
+    WrongPhaseExpectedCommit     = 26,
+    WrongPhaseExpectedReveal     = 27,
+    WrongPhaseExpectedRoleChoice = 28,
+    DelegationLimit      = 29,
+    DelegationNotFound   = 30,
+    TranscriptInvalid    = 31,
+    NameTooLong          = 32,
+    InvalidEmote         = 33,
+    EmoteRateLimited     = 34,
+    LabelTooLong         = 35,
+    GameNotFinished      = 36,
+    SessionReserved      = 37,
+    InvalidRange         = 38,
+    NotWatching          = 39,
+    Blocked              = 40,
+    SettlementPending    = 41,
+    NoPendingSettlement  = 42,
+    DisputeWindowClosed  = 43,
+    NumberOutOfRange     = 44,
+    GameDrawn            = 45,
+    TimeoutNotReached    = 46,
+    NotOpponentsTurn     = 47,
+    VerifyingKeyInvalid  = 48,
+    DuplicateCommitment  = 49,
+    ProofMalformed       = 50,
+    TooManyActiveGames   = 51,
+    BatchTooLarge        = 52,
+    SeriesGame           = 53,
+    InvalidSeriesLength  = 54,
+    SeriesNotFound       = 55,
+    SuperOverTooLong     = 56,
+    NotBatter            = 57,
+    NotFirstInnings      = 58,
+    InvalidNumberRange   = 59,
+    ChallengeNotFound    = 60,
+    ChallengeExpired     = 61,
+    LobbyFull            = 62,
+    AlreadyJoined        = 63,
+    PracticeGame         = 64,
+    NoPendingAdmin       = 65,
+    InvalidGameTtl       = 66,
+    MigrationPending     = 67,
+    StakeTooLow          = 68,
+    StakeTooHigh         = 69,
+    InvalidStakeLimits   = 70,
+    UnequalStakes        = 71,
+    FeeTooHigh           = 72,
+    EscrowGame           = 73,
+    NoHub                = 74,
+    DirectStartDisabled  = 75,
+    HubNotAllowed        = 76,
+    ResultAlreadyReported = 77,
+    HubReportFailed      = 78,
+    LeafCountInvalid     = 79,
+    NoMerkleRoot         = 80,
+    LeafOutOfOrder       = 81,
+    NoSigningKeys        = 82,
+    NoSeriesGamePending  = 83,
 }
 
 #[contracttype]
@@ -35,6 +123,9 @@ pub enum Phase {
     BatBowlChoice,
     BallCommit,
     BallReveal,
+    // The tiebreak after a level chase; see `get_super_over_balls`. Each ball is committed and revealed
+    // within this one phase, reveals opening once both players have committed.
+    SuperOver,
     Finished,
 }
 
@@ -57,255 +148,4181 @@ pub struct Game {
     pub innings: u32,
     pub target: u32,
     pub phase: Phase,
+    // Stays None in a Finished game that was drawn.
     pub winner: Option<Address>,
+    pub vk_version: Option<u32>,
+    // Ledger sequence the current phase (or, during play, the current ball) began at.
+    pub phase_started_ledger: u32,
+    // Ledger of the last write, which is also when the entry's TTL was last extended.
+    pub last_action_ledger: u32,
+    // Rounds resolved so far, toss included: the index of the round in progress.
+    pub rounds: u32,
+    // Set at start and never changed; see `start_labeled_game`.
+    pub label: Option<Symbol>,
+    pub organizer: Option<Address>,
+    // 0 for unlimited; see `start_limited_game`.
+    pub max_balls_per_innings: u32,
+    // Balls resolved in the current innings.
+    pub balls_bowled: u32,
+    // Innings 3 and 4 are the halves of super over number `super_over`, counted from 1, with the innings-2
+    // batter batting first. The super-over scores are the current super over's and leave p1_score and
+    // p2_score alone; `super_over_balls` is the per-side limit, fixed when the first super over starts.
+    pub super_over: u32,
+    pub super_over_balls: u32,
+    pub p1_super_score: u32,
+    pub p2_super_score: u32,
+    // Numbers a reveal may open to, inclusive; see `start_configured_game`.
+    pub min_number: u32,
+    pub max_number: u32,
+    // Bonus runs for player1 when positive, player2 when negative; see `start_handicapped_game`.
+    pub handicap: i32,
+    // Player2 is this contract, drawing its numbers at reveal time; see `start_practice_game`.
+    pub is_practice: bool,
+    // Set by `admin_void_game`; a voided game is Finished with no winner and was cancelled on the hub, or
+    // refunded if escrowed.
+    pub voided: bool,
+    // Set by `start_game_with_token`: each player escrowed their points of this token here, and the game
+    // pays out itself instead of through the hub.
+    pub escrow_token: Option<Address>,
+    // Played for fun with no stakes and no hub session; see `start_friendly_game`.
+    pub friendly: bool,
+    // Hub the session was opened on, which hears its result even if `set_hub` changes mid-game. None for games
+    // outside the hub, and for games from before it was recorded, which report to the current hub.
+    pub hub: Option<Address>,
+    // Whether the hub has the result of this finished game: false while the hub has failed to take it or it is
+    // held for the dispute window; see `report_result`. Games outside the hub set it as they finish.
+    pub result_reported: bool,
+    // ed25519 keys the players sign `settle_signed_balls` moves with; set by `start_game_with_keys`.
+    pub p1_key: Option<BytesN<32>>,
+    pub p2_key: Option<BytesN<32>>,
 }
 
+/// Per-game options for `start_configured_game`, in any combination; the default is a plain `start_game`.
 #[contracttype]
-#[derive(Clone)]
-pub enum DataKey {
-    Game(u32),
-    GameHubAddress,
-    Admin,
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameConfig {
+    // Reveals must fall in `min_number..=max_number`.
+    pub min_number: u32,
+    pub max_number: u32,
+    // 0 for unlimited.
+    pub max_balls_per_innings: u32,
+    // Bonus runs for player1 when positive, player2 when negative.
+    pub handicap: i32,
+    // ed25519 keys the players sign `settle_signed_balls` moves with.
+    pub p1_key: Option<BytesN<32>>,
+    pub p2_key: Option<BytesN<32>>,
 }
 
-const GAME_TTL_LEDGERS: u32 = 518_400;
-
-fn verify_proof(env: &Env, stored_commitment: &BytesN<32>, number: u32, proof_blob: &Bytes) -> bool {
-    if proof_blob.len() < 132 {
-        return false;
-    }
-    let n0 = proof_blob.get(0).unwrap_or(0) as u32;
-    let n1 = proof_blob.get(1).unwrap_or(0) as u32;
-    let n2 = proof_blob.get(2).unwrap_or(0) as u32;
-    let n3 = proof_blob.get(3).unwrap_or(0) as u32;
-    let num_inputs = (n0 << 24) | (n1 << 16) | (n2 << 8) | n3;
-    if num_inputs != 2 {
-        return false;
-    }
-    let mut proof_commitment_bytes = [0u8; 32];
-    for i in 0..32 {
-        proof_commitment_bytes[i] = proof_blob.get(4 + i as u32).unwrap_or(0);
+impl Default for GameConfig {
+    fn default() -> Self {
+        GameConfig { min_number: MIN_NUMBER, max_number: MAX_NUMBER, max_balls_per_innings: 0, handicap: 0, p1_key: None, p2_key: None }
     }
-    let proof_commitment: BytesN<32> = BytesN::from_array(env, &proof_commitment_bytes);
-    if proof_commitment != *stored_commitment {
-        return false;
-    }
-    let r0 = proof_blob.get(64).unwrap_or(0) as u32;
-    let r1 = proof_blob.get(65).unwrap_or(0) as u32;
-    let r2 = proof_blob.get(66).unwrap_or(0) as u32;
-    let r3 = proof_blob.get(67).unwrap_or(0) as u32;
-    let proof_number = (r0 << 24) | (r1 << 16) | (r2 << 8) | r3;
-    proof_number == number
 }
 
-#[contract]
-pub struct HandCricketContract;
+/// Tag for grouping games, passed to `start_labeled_game`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameLabel {
+    pub label: Symbol,
+    pub organizer: Option<Address>,
+}
 
-#[contractimpl]
-impl HandCricketContract {
+/// A game as stored in `StoredGame::V1` and, before that, as a bare entry: the layout the contract shipped
+/// with, before timing, verifying key pinning and every game option.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameV1 {
+    pub player1: Address,
+    pub player2: Address,
+    pub player1_points: i128,
+    pub player2_points: i128,
+    pub player1_is_odd: bool,
+    pub toss_winner: Option<Address>,
+    pub batter: Option<Address>,
+    pub p1_commitment: Option<BytesN<32>>,
+    pub p2_commitment: Option<BytesN<32>>,
+    pub p1_number: Option<u32>,
+    pub p2_number: Option<u32>,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub innings: u32,
+    pub target: u32,
+    pub phase: Phase,
+    pub winner: Option<Address>,
+}
 
-    pub fn __constructor(env: Env, admin: Address, game_hub: Address) {
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::GameHubAddress, &game_hub);
-    }
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyingKey {
+    pub key: Bytes,
+    pub retired: bool,
+}
 
-    pub fn start_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128) -> Result<(), Error> {
-        if player1 == player2 { return Err(Error::SelfPlay); }
-        player1.require_auth_for_args(vec![&env, session_id.into_val(&env), player1_points.into_val(&env)]);
-        player2.require_auth_for_args(vec![&env, session_id.into_val(&env), player2_points.into_val(&env)]);
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum Invariant {
+    WinnerOnlyWhenFinished   = 1,
+    WinnerIsPlayer           = 2,
+    NumbersOnlyWhenRevealing = 3,
+    NumberWithoutCommitment  = 4,
+    CommitmentsMatchPhase    = 5,
+    BatterSetDuringBalls     = 6,
+    TossWinnerSetAfterToss   = 7,
+    InningsAndTarget         = 8,
+    BallsWithinLimit         = 9,
+}
 
-        let mut seed_bytes = Bytes::new(&env);
-        seed_bytes.append(&Bytes::from_array(&env, &session_id.to_be_bytes()));
-        seed_bytes.append(&player1.to_string().to_bytes());
-        seed_bytes.append(&player2.to_string().to_bytes());
-        let seed_hash = env.crypto().keccak256(&seed_bytes);
-        let seed_array = seed_hash.to_array();
-        let player1_is_odd = seed_array[31] % 2 == 0;
+/// What any observer may see of a game; live commitments and numbers are reduced to flags.
+/// Returned by `get_game_public` and by every mutating gameplay entrypoint.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GamePublicView {
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    pub player1_points: i128,
+    pub player2_points: i128,
+    pub player1_is_odd: bool,
+    pub toss_winner: Option<Address>,
+    pub batter: Option<Address>,
+    pub p1_committed: bool,
+    pub p2_committed: bool,
+    pub p1_revealed: bool,
+    pub p2_revealed: bool,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub innings: u32,
+    pub target: u32,
+    pub phase: Phase,
+    pub winner: Option<Address>,
+    pub phase_started_ledger: u32,
+    // Cosmetic display names, if the players have set one; see `set_display_name`.
+    pub p1_name: Option<Symbol>,
+    pub p2_name: Option<Symbol>,
+    pub label: Option<Symbol>,
+    pub organizer: Option<Address>,
+    // None in games without a ball limit. During a super over, what is left of the current half.
+    pub balls_remaining: Option<u32>,
+    pub super_over: u32,
+    pub p1_super_score: u32,
+    pub p2_super_score: u32,
+    pub min_number: u32,
+    pub max_number: u32,
+}
 
-        let game_hub_addr: Address = env.storage().instance().get(&DataKey::GameHubAddress).expect("GameHub not set");
-        let game_hub = GameHubClient::new(&env, &game_hub_addr);
-        game_hub.start_game(&env.current_contract_address(), &session_id, &player1, &player2, &player1_points, &player2_points);
+/// Returned by `reveal_and_commit_next`: the game after the call, and whether the next ball's commitment was
+/// kept. It is dropped when the reveal didn't leave a fresh ball to commit to in the same innings.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PipelinedReveal {
+    pub game: GamePublicView,
+    pub next_committed: bool,
+}
 
-        let game = Game {
-            player1, player2, player1_points, player2_points,
-            player1_is_odd, toss_winner: None, batter: None,
-            p1_commitment: None, p2_commitment: None,
-            p1_number: None, p2_number: None,
-            p1_score: 0, p2_score: 0, innings: 1, target: 0,
-            phase: Phase::TossCommit, winner: None,
-        };
+/// Every layout a stored game has had. A new layout adds a variant and a step in `upgrade_stored_game`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+// Only ever held one at a time, on the way to or from storage.
+#[allow(clippy::large_enum_variant)]
+pub enum StoredGame {
+    V1(GameV1),
+    V2(Game),
+}
 
-        let key = DataKey::Game(session_id);
-        env.storage().temporary().set(&key, &game);
-        env.storage().temporary().extend_ttl(&key, GAME_TTL_LEDGERS, GAME_TTL_LEDGERS);
-        Ok(())
-    }
+/// Self-describing snapshot of one session, moved between deployments by `export_game` / `import_game`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MigrationBlob {
+    pub schema_version: u32,
+    pub source: Address,
+    // The hub the session is registered with; the receiving deployment must settle through the same one.
+    // None for practice and friendly games, which have no hub session.
+    pub hub: Option<Address>,
+    pub session_id: u32,
+    pub game: StoredGame,
+}
 
-    pub fn commit_number(env: Env, session_id: u32, player: Address, commitment: BytesN<32>) -> Result<(), Error> {
-        player.require_auth();
-        let key = DataKey::Game(session_id);
-        let mut game: Game = env.storage().temporary().get(&key).ok_or(Error::GameNotFound)?;
-        if game.winner.is_some() { return Err(Error::GameAlreadyEnded); }
-        match game.phase { Phase::TossCommit | Phase::BallCommit => {} _ => return Err(Error::WrongPhase), }
+/// One confirmed interface revision; see `get_version_history`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VersionRecord {
+    pub version: u32,
+    pub wasm_hash: BytesN<32>,
+    pub ledger: u32,
+}
 
-        if player == game.player1 {
-            if game.p1_commitment.is_some() { return Err(Error::AlreadyCommitted); }
-            game.p1_commitment = Some(commitment);
-        } else if player == game.player2 {
-            if game.p2_commitment.is_some() { return Err(Error::AlreadyCommitted); }
-            game.p2_commitment = Some(commitment);
-        } else { return Err(Error::NotPlayer); }
+/// A player's `commit_merkle` root, standing in as their commitment for each ball of `innings` until its
+/// leaves run out.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleCommitment {
+    pub root: BytesN<32>,
+    pub leaf_count: u32,
+    // The leaf `reveal_leaf` opens next.
+    pub next_index: u32,
+    pub innings: u32,
+}
 
-        if game.p1_commitment.is_some() && game.p2_commitment.is_some() {
-            game.phase = match game.phase {
-                Phase::TossCommit => Phase::TossReveal,
-                Phase::BallCommit => Phase::BallReveal,
-                _ => return Err(Error::WrongPhase),
-            };
-        }
+/// One ball of a `settle_signed_balls` transcript. Both signatures are over `signed_ball_message`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BallMove {
+    // `Game::rounds` when the ball is played, as in `BallPlayed::ball`.
+    pub ball_index: u32,
+    // The batter the players played the ball under; not signed, so it can only cut a transcript short.
+    pub p1_batting: bool,
+    pub p1_number: u32,
+    pub p2_number: u32,
+    pub p1_signature: BytesN<64>,
+    pub p2_signature: BytesN<64>,
+}
 
-        env.storage().temporary().set(&key, &game);
-        env.storage().temporary().extend_ttl(&key, GAME_TTL_LEDGERS, GAME_TTL_LEDGERS);
-        Ok(())
-    }
+/// A player's standing permission for another address to submit their moves.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Delegation {
+    pub delegate: Address,
+    // None covers every session the player is in.
+    pub session_id: Option<u32>,
+}
 
-    pub fn reveal_number(env: Env, session_id: u32, player: Address, number: u32, proof_blob: Bytes) -> Result<(), Error> {
-        player.require_auth();
-        let key = DataKey::Game(session_id);
-        let mut game: Game = env.storage().temporary().get(&key).ok_or(Error::GameNotFound)?;
-        if game.winner.is_some() { return Err(Error::GameAlreadyEnded); }
-        match game.phase { Phase::TossReveal | Phase::BallReveal => {} _ => return Err(Error::WrongPhase), }
+/// Session ids `from..=to` held for `organizer` until `expires_ledger`; see `reserve_sessions`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Reservation {
+    pub organizer: Address,
+    pub from: u32,
+    pub to: u32,
+    pub expires_ledger: u32,
+}
 
-        if player == game.player1 {
-            if game.p1_number.is_some() { return Err(Error::AlreadyRevealed); }
-            let commitment = game.p1_commitment.as_ref().ok_or(Error::CommitMissing)?;
-            if !verify_proof(&env, commitment, number, &proof_blob) { return Err(Error::ProofInvalid); }
-            game.p1_number = Some(number);
-        } else if player == game.player2 {
-            if game.p2_number.is_some() { return Err(Error::AlreadyRevealed); }
-            let commitment = game.p2_commitment.as_ref().ok_or(Error::CommitMissing)?;
-            if !verify_proof(&env, commitment, number, &proof_blob) { return Err(Error::ProofInvalid); }
-            game.p2_number = Some(number);
-        } else { return Err(Error::NotPlayer); }
+/// A session id `reserve_session` handed to `creator`, who must play in the game started on it.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorReservation {
+    pub creator: Address,
+    pub expires_ledger: u32,
+}
 
-        if game.p1_number.is_some() && game.p2_number.is_some() {
-            match game.phase {
-                Phase::TossReveal => { game = Self::resolve_toss(game); }
-                Phase::BallReveal => { game = Self::resolve_ball(&env, session_id, game)?; }
-                _ => {}
-            }
-        }
+/// A game offered by `create_challenge`, waiting for `opponent` to accept it before `expires_ledger`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Challenge {
+    pub challenger: Address,
+    pub opponent: Address,
+    pub challenger_points: i128,
+    pub opponent_points: i128,
+    pub expires_ledger: u32,
+}
 
-        env.storage().temporary().set(&key, &game);
-        env.storage().temporary().extend_ttl(&key, GAME_TTL_LEDGERS, GAME_TTL_LEDGERS);
-        Ok(())
-    }
+/// A game posted by `create_open_game` for whoever joins first, staking `opponent_points`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct OpenGame {
+    pub host: Address,
+    pub host_points: i128,
+    pub opponent_points: i128,
+    pub expires_ledger: u32,
+}
 
-    pub fn choose_role(env: Env, session_id: u32, player: Address, bat: bool) -> Result<(), Error> {
-        player.require_auth();
-        let key = DataKey::Game(session_id);
-        let mut game: Game = env.storage().temporary().get(&key).ok_or(Error::GameNotFound)?;
-        if game.phase != Phase::BatBowlChoice { return Err(Error::WrongPhase); }
-        let toss_winner = game.toss_winner.as_ref().ok_or(Error::WrongPhase)?;
-        if &player != toss_winner { return Err(Error::NotTossWinner); }
+/// Bounds, inclusive, on each player's stake when a game starts; see `set_stake_limits`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StakeLimits {
+    pub min_points: i128,
+    pub max_points: i128,
+}
 
-        game.batter = if bat {
-            Some(player.clone())
-        } else {
-            if player == game.player1 { Some(game.player2.clone()) } else { Some(game.player1.clone()) }
-        };
+/// The latest operator notice from `announce`, e.g. an upcoming upgrade. Only the hash of the message is
+/// kept on chain; clients fetch the text off chain.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Announcement {
+    pub id: u32,
+    pub kind: Symbol,
+    pub starts_at_ledger: u32,
+    pub message_hash: BytesN<32>,
+}
 
-        game.p1_commitment = None; game.p2_commitment = None;
-        game.p1_number = None; game.p2_number = None;
-        game.phase = Phase::BallCommit;
+/// Deployment branding from `set_branding`. The site is referenced by the hash of its URL only.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Branding {
+    pub name: Symbol,
+    pub url_hash: BytesN<32>,
+    // 0xRRGGBB.
+    pub accent: u32,
+}
 
-        env.storage().temporary().set(&key, &game);
-        env.storage().temporary().extend_ttl(&key, GAME_TTL_LEDGERS, GAME_TTL_LEDGERS);
-        Ok(())
-    }
+/// `get_metadata`: the contract's built-in identity, with the deployment's branding or, until the operator
+/// sets one, the contract name, a zero hash and accent 0.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Metadata {
+    pub contract: Symbol,
+    pub version: u32,
+    pub name: Symbol,
+    pub url_hash: BytesN<32>,
+    pub accent: u32,
+}
 
-    pub fn get_game(env: Env, session_id: u32) -> Result<Game, Error> {
-        let key = DataKey::Game(session_id);
-        env.storage().temporary().get(&key).ok_or(Error::GameNotFound)
-    }
+/// One `block_address` or `unblock_address` call, as kept in an address's moderation log.
+/// `reason` is the caller's reason code; unblocking records 0.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ModerationEntry {
+    pub actor: Address,
+    pub action: Symbol,
+    pub reason: u32,
+    pub ledger: u32,
+}
 
-    fn resolve_toss(mut game: Game) -> Game {
-        let sum = game.p1_number.unwrap_or(0) + game.p2_number.unwrap_or(0);
-        let sum_is_odd = sum % 2 == 1;
-        let player1_wins = (game.player1_is_odd && sum_is_odd) || (!game.player1_is_odd && !sum_is_odd);
-        game.toss_winner = if player1_wins { Some(game.player1.clone()) } else { Some(game.player2.clone()) };
-        game.p1_number = None; game.p2_number = None;
-        game.p1_commitment = None; game.p2_commitment = None;
-        game.phase = Phase::BatBowlChoice;
-        game
-    }
+/// What stays of a finished game once its temporary `Game` entry expires; see `get_result`.
+/// `winner` is None for a draw.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GameResult {
+    pub player1: Address,
+    pub player2: Address,
+    pub winner: Option<Address>,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub target: u32,
+    pub finished_ledger: u32,
+}
 
-    fn resolve_ball(env: &Env, session_id: u32, mut game: Game) -> Result<Game, Error> {
-        let p1_num = game.p1_number.unwrap_or(0);
-        let p2_num = game.p2_number.unwrap_or(0);
-        let batter = game.batter.as_ref().ok_or(Error::WrongPhase)?.clone();
-        let is_out = p1_num == p2_num;
-
-        if is_out {
-            if game.innings == 1 {
-                let score = if batter == game.player1 { game.p1_score } else { game.p2_score };
-                game.target = score + 1;
-                game.innings = 2;
-                game.batter = if batter == game.player1 { Some(game.player2.clone()) } else { Some(game.player1.clone()) };
-                game.p1_commitment = None; game.p2_commitment = None;
-                game.p1_number = None; game.p2_number = None;
-                game.phase = Phase::BallCommit;
-            } else {
-                let winner = if batter == game.player1 { game.player2.clone() } else { game.player1.clone() };
-                game.winner = Some(winner.clone());
-                game.phase = Phase::Finished;
-                Self::call_end_game(env, session_id, winner == game.player1);
-            }
-        } else {
-            if batter == game.player1 {
-                game.p1_score += p1_num;
-                if game.innings == 2 && game.p1_score >= game.target {
-                    game.winner = Some(game.player1.clone());
-                    game.phase = Phase::Finished;
-                    Self::call_end_game(env, session_id, true);
-                    return Ok(game);
-                }
-            } else {
-                game.p2_score += p2_num;
-                if game.innings == 2 && game.p2_score >= game.target {
-                    game.winner = Some(game.player2.clone());
-                    game.phase = Phase::Finished;
-                    Self::call_end_game(env, session_id, false);
-                    return Ok(game);
-                }
-            }
-            game.p1_commitment = None; game.p2_commitment = None;
-            game.p1_number = None; game.p2_number = None;
-            game.phase = Phase::BallCommit;
-        }
-        Ok(game)
-    }
+/// A best-of-N match played as consecutive games; see `start_series`. The hub sees one session, `series_id`,
+/// settled once a side reaches `games_to_win`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Series {
+    pub player1: Address,
+    pub player2: Address,
+    pub player1_points: i128,
+    pub player2_points: i128,
+    pub games_to_win: u32,
+    // Session ids of the series' games in order; the last is in play until the series has a winner. Once it
+    // has a result and the series none, the next game is waiting on `start_next_series_game`.
+    pub sessions: Vec<u32>,
+    // Winner of each finished game, None for a draw.
+    pub results: Vec<Option<Address>>,
+    pub p1_wins: u32,
+    pub p2_wins: u32,
+    pub winner: Option<Address>,
+}
 
-    fn call_end_game(env: &Env, session_id: u32, player1_won: bool) {
-        let game_hub_addr: Address = env.storage().instance().get(&DataKey::GameHubAddress).expect("GameHub not set");
-        GameHubClient::new(env, &game_hub_addr).end_game(&session_id, &player1_won);
-    }
+/// A player's career across every game they finished; see `get_stats`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerStats {
+    pub games_played: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub draws: u32,
+    pub total_runs_scored: u32,
+    pub total_wickets_taken: u32,
+}
 
-    pub fn get_admin(env: Env) -> Address { env.storage().instance().get(&DataKey::Admin).expect("Admin not set") }
-    pub fn set_admin(env: Env, new_admin: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not set");
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::Admin, &new_admin);
-    }
-    pub fn get_hub(env: Env) -> Address { env.storage().instance().get(&DataKey::GameHubAddress).expect("GameHub not set") }
-    pub fn set_hub(env: Env, new_hub: Address) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not set");
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::GameHubAddress, &new_hub);
-    }
-    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>) {
-        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not set");
-        admin.require_auth();
-        env.deployer().update_current_contract_wasm(new_wasm_hash);
-    }
+/// A ranked player; see `get_leaderboard`. Mirrors their `PlayerStats`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeaderboardEntry {
+    pub player: Address,
+    pub wins: u32,
+    pub runs: u32,
+}
+
+/// Finished games between two players; see `get_head_to_head`. `last_session` is None until they finish one.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct HeadToHead {
+    pub p_a_wins: u32,
+    pub p_b_wins: u32,
+    pub draws: u32,
+    pub last_session: Option<u32>,
+}
+
+/// A finished game's hub result held back for the dispute window; see `finalize` and `dispute`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PendingSettlement {
+    // None for a draw.
+    pub player1_won: Option<bool>,
+    pub unlock_ledger: u32,
+}
+
+/// Optional settings for `__constructor`. Unset members keep the default their getter reports.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InitConfig {
+    pub operator: Option<Address>,
+    pub rollback_window_ledgers: Option<u32>,
+    pub forward_starts: Option<bool>,
+    // Rejected with FeatureDisabled by builds without `zk`.
+    pub accept_any_vk: Option<bool>,
+    // Toggleable feature bits to start switched off.
+    pub disabled_features: Option<u32>,
+    pub reservation_ledgers: Option<u32>,
+    pub dispute_window_ledgers: Option<u32>,
+    pub timeout_ledgers: Option<u32>,
+    pub verifier: Option<Address>,
+    pub super_over_balls: Option<u32>,
+    pub open_game_ledgers: Option<u32>,
+    pub game_ttl_ledgers: Option<u32>,
+    pub min_stake_points: Option<i128>,
+    pub max_stake_points: Option<i128>,
+    pub require_equal_stakes: Option<bool>,
+    pub fee_bps: Option<u32>,
+    pub legacy_hub: Option<bool>,
+    pub hub_supports_cancel: Option<bool>,
+    pub direct_start_allowed: Option<bool>,
+}
+
+/// Every deployment setting in one read; see `get_config`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub admin: Address,
+    pub hub: Option<Address>,
+    pub operator: Address,
+    pub rollback_window_ledgers: u32,
+    pub forward_starts: bool,
+    pub accept_any_vk: bool,
+    pub enabled_features: u32,
+    pub reservation_ledgers: u32,
+    pub dispute_window_ledgers: u32,
+    pub timeout_ledgers: u32,
+    pub verifier: Option<Address>,
+    pub super_over_balls: u32,
+    pub open_game_ledgers: u32,
+    pub game_ttl_ledgers: u32,
+    pub stake_limits: StakeLimits,
+    pub require_equal_stakes: bool,
+    pub fee_bps: u32,
+    pub legacy_hub: bool,
+    pub hub_supports_cancel: bool,
+    pub direct_start_allowed: bool,
+}
+
+/// One administrative action for `admin_multicall`; each wraps the arguments of the entrypoint it names.
+/// Gameplay, upgrades and retirement are deliberately not batchable.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+// Ops are decoded one at a time from a host Vec, so the ImportGame variant's size never multiplies.
+#[allow(clippy::large_enum_variant)]
+pub enum AdminOp {
+    ProposeAdmin(Address),
+    SetHub(Address),
+    SetOperator(Address),
+    SetDeprecated(Address),
+    SetForwardStarts(bool),
+    SetRollbackWindow(u32),
+    SetAcceptAnyVk(bool),
+    AddVerifyingKey(u32, Bytes),
+    RetireVerifyingKey(u32),
+    ImportGame(MigrationBlob),
+    SetReservationLedgers(u32),
+    SetDisputeWindow(u32),
+    SetTimeoutLedgers(u32),
+    SetVerifier(Option<Address>),
+    SetSuperOverBalls(u32),
+    SetOpenGameLedgers(u32),
+    SetGameTtl(u32),
+    SetStakeLimits(StakeLimits),
+    SetRequireEqualStakes(bool),
+    SetFeeBps(u32),
+    SetLegacyHub(bool),
+    SetHubSupportsCancel(bool),
+    SetDirectStartAllowed(bool),
+    AllowHub(Address),
+    DisallowHub(Address),
+    // Operator role
+    EnableFeature(u32),
+    DisableFeature(u32),
+}
+
+/// What a player has to do next for the game to progress.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum PendingAction {
+    Commit     = 1,
+    Reveal     = 2,
+    ChooseRole = 3,
+    // Waiting on the opponent
+    Wait       = 4,
+    // Game finished
+    None       = 5,
+}
+
+/// What a given address should do next in a game, with enough detail for a client to explain it.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[repr(u32)]
+pub enum ExpectedAction {
+    CommitToss           = 1,
+    RevealToss           = 2,
+    AwaitOpponentCommit  = 3,
+    ChooseRole           = 4,
+    AwaitRoleChoice      = 5,
+    CommitBall           = 6,
+    RevealBall           = 7,
+    AwaitOpponentReveal  = 8,
+    GameFinished         = 9,
+    NotParticipant       = 10,
+}
+
+/// Whose move a game is waiting on; see `get_pending_action`. The vectors hold only the players who still
+/// owe the current phase's move, player1 first. `PendingAction` is the same answer for one player.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Turn {
+    CommitFrom(Vec<Address>),
+    RevealFrom(Vec<Address>),
+    RoleChoiceFrom(Address),
+    // Game finished
+    None,
+}
+
+/// Diagnostic view over a stored game; see `debug_dump`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DebugReport {
+    pub game: Game,
+    pub failed_invariants: Vec<Invariant>,
+    pub player1_action: PendingAction,
+    pub player2_action: PendingAction,
+    pub ledgers_in_phase: u32,
+    // Derived from last_action_ledger, since contracts cannot read TTLs directly.
+    pub ttl_remaining: u32,
+    // Counted from last_action_ledger, as claim_timeout enforces it; None while no single player owes a move.
+    pub timeout_deadline: Option<u32>,
+    // The hub result still held back by the dispute window: empty or one entry. A Vec because the SDK has no
+    // XDR conversion for an Option of a contract type.
+    pub pending_settlement: Vec<PendingSettlement>,
+}
+
+// Every variant exists in every feature set, so storage written by one build reads back in any other.
+#[contracttype]
+#[derive(Clone)]
+pub enum DataKey {
+    Game(u32),
+    GameHubAddress,
+    Admin,
+    // Set by `propose_admin` until the proposed address accepts.
+    PendingAdmin,
+    VerifyingKey(u32),
+    ActiveVkVersions,
+    AcceptAnyVk,
+    CodeVersion,
+    PendingCodeVersion,
+    Successor,
+    ActiveSessions,
+    Retired,
+    CurrentWasmHash,
+    PreviousWasmHash,
+    PreviousCodeVersion,
+    UpgradedAtLedger,
+    RollbackWindow,
+    Operator,
+    FeatureToggles,
+    VersionHistory,
+    ForwardStarts,
+    Delegations(Address),
+    Name(Address),
+    // The round a player last sent an emote in.
+    Emote(u32, Address),
+    MerkleCommitment(u32, Address),
+    // Set by `purge_my_data`: nothing player-keyed is recorded for them until they opt back in.
+    StatsOptOut(Address),
+    Reservations,
+    ReservationLedgers,
+    // Watches are counted per subject; the pair entry only stops one watcher counting twice.
+    Watchers(Address),
+    Watching(Address, Address),
+    CreatorReservation(u32),
+    NextReservedSession,
+    NextSession,
+    Challenge(u32),
+    OpenGame(u32),
+    // Session ids of the posted open games, oldest first; see `list_open_games`.
+    OpenGames,
+    OpenGameLedgers,
+    GameTtl,
+    StakeLimits,
+    RequireEqualStakes,
+    FeeBps,
+    FeesAccrued,
+    // Fee withheld from a hub session's stakes, until it settles.
+    SessionFee(u32),
+    LegacyHub,
+    HubSupportsCancel,
+    DirectStartAllowed,
+    // Extra hubs `start_game_on_hub` may open sessions on.
+    AllowedHubs,
+    Announcement,
+    Branding,
+    Blocked(Address),
+    ModerationLog(Address),
+    DisputeWindow,
+    TimeoutLedgers,
+    PendingSettlement(u32),
+    VerifierAddress,
+    // Persistent, unlike the game itself.
+    Result(u32),
+    // Sessions a player is in that have not finished, oldest first.
+    PlayerGames(Address),
+    Stats(Address),
+    // The lower address first, so either seating shares one record.
+    HeadToHead(Address, Address),
+    // Everyone a player shares a head-to-head record with, so `purge_my_data` can find those records.
+    Opponents(Address),
+    Leaderboard,
+    Series(u32),
+    // The series a game belongs to.
+    SeriesGame(u32),
+    SuperOverBalls,
+}
+
+/// Emitted by `upgrade` just before the wasm is swapped.
+#[contractevent]
+pub struct UpgradeScheduled {
+    pub from_version: u32,
+    pub to_version: u32,
+    pub forced: bool,
+}
+
+/// Emitted by `set_deprecated`; new games should go to `successor`. `announcement` is the id of the
+/// announcement live at the time, if any.
+#[contractevent]
+pub struct Deprecated {
+    pub successor: Address,
+    pub announcement: Option<u32>,
+}
+
+/// Emitted by `announce`.
+#[contractevent]
+pub struct Announced {
+    #[topic]
+    pub kind: Symbol,
+    pub id: u32,
+    pub starts_at_ledger: u32,
+    pub message_hash: BytesN<32>,
 }
+
+/// Emitted by `set_branding`.
+#[contractevent]
+pub struct BrandingChanged {
+    pub name: Symbol,
+    pub url_hash: BytesN<32>,
+    pub accent: u32,
+}
+
+/// Emitted by `start_game_redirectable` when a start was forwarded to the successor instead of played here.
+#[contractevent]
+pub struct StartRedirected {
+    pub session_id: u32,
+    pub successor: Address,
+}
+
+/// Emitted by `retire`; the contract accepts no further writes.
+#[contractevent]
+pub struct Retired {}
+
+/// Emitted by `rollback` just before the previous wasm is swapped back in.
+#[contractevent]
+pub struct RolledBack {
+    pub to_wasm_hash: BytesN<32>,
+    pub to_version: u32,
+}
+
+/// Emitted by `export_game`; `blob_hash` is keccak256 of the blob's XDR.
+#[contractevent]
+pub struct GameExported {
+    pub session_id: u32,
+    pub successor: Address,
+    pub blob_hash: BytesN<32>,
+    pub phase: Symbol,
+}
+
+/// Emitted by `import_game` with the same `blob_hash` the source emitted.
+#[contractevent]
+pub struct GameImported {
+    pub session_id: u32,
+    pub source: Address,
+    pub blob_hash: BytesN<32>,
+    pub phase: Symbol,
+}
+
+/// Emitted by `send_emote`; emotes are event-only.
+#[contractevent(topics = ["hand_cricket", "emote"])]
+pub struct Emote {
+    #[topic]
+    pub session_id: u32,
+    pub sender: Address,
+    pub emote: u32,
+}
+
+// Lifecycle events under the `hcricket` topic, with the session id as a topic so one match can be followed.
+
+/// Emitted when a game is opened. In this and the other game events, `phase` is the game's `phase_symbol`
+/// once the event's change has been applied.
+#[contractevent(topics = ["hcricket", "start"])]
+pub struct GameStarted {
+    #[topic]
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    pub phase: Symbol,
+}
+
+/// Emitted when the toss resolves, with both numbers played.
+#[contractevent(topics = ["hcricket", "toss"])]
+pub struct TossResolved {
+    #[topic]
+    pub session_id: u32,
+    pub p1_number: u32,
+    pub p2_number: u32,
+    pub toss_winner: Address,
+    pub phase: Symbol,
+}
+
+/// Emitted for every ball, the one that ends the game included. `ball` is the ball's `Game::rounds`
+/// index (1 for the first ball, counting through both innings); `innings` is the innings it was
+/// bowled in. `runs` is 0 when `out`. Scores and `target` are as they stand after the ball; in a super over
+/// (innings 3 and 4) they are the super over's, with `target` 0 for its first half.
+#[contractevent(topics = ["hcricket", "ball"])]
+pub struct BallPlayed {
+    #[topic]
+    pub session_id: u32,
+    pub innings: u32,
+    pub ball: u32,
+    pub batter_number: u32,
+    pub bowler_number: u32,
+    pub runs: u32,
+    pub out: bool,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub target: u32,
+    pub phase: Symbol,
+}
+
+/// Emitted by `declare`; `player` closed the first innings on `score`, leaving `target` to chase.
+#[contractevent(topics = ["hcricket", "declare"])]
+pub struct Declared {
+    #[topic]
+    pub session_id: u32,
+    pub player: Address,
+    pub score: u32,
+    pub target: u32,
+}
+
+/// Emitted when the toss winner picks a role; `batter` bats first.
+#[contractevent(topics = ["hcricket", "role"])]
+pub struct RoleChosen {
+    #[topic]
+    pub session_id: u32,
+    pub batter: Address,
+    pub phase: Symbol,
+}
+
+/// Emitted however a game finishes; `winner` is None for a draw. `result` is the game's `result_symbol`.
+#[contractevent(topics = ["hcricket", "end"])]
+pub struct GameEnded {
+    #[topic]
+    pub session_id: u32,
+    pub winner: Option<Address>,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub phase: Symbol,
+    pub result: Symbol,
+}
+
+/// Emitted alongside a game starting (`started`) or finishing (`finished`), once per participant
+/// who has at least one watcher, so subscriptions can filter on the subject.
+#[contractevent(topics = ["hand_cricket", "watched"])]
+pub struct Watched {
+    #[topic]
+    pub subject: Address,
+    pub session_id: u32,
+    pub kind: Symbol,
+}
+
+/// Emitted by `cancel_game` and `expire_stale_games`; the hub has been told to return both stakes.
+#[contractevent]
+pub struct GameCancelled {
+    #[topic]
+    pub session_id: u32,
+}
+
+/// Emitted by `report_fees_collected` with the amount it cleared.
+#[contractevent]
+pub struct FeesCollected {
+    pub amount: i128,
+}
+
+/// Emitted by `admin_void_game`, naming the admin who voided the game.
+#[contractevent]
+pub struct GameVoided {
+    #[topic]
+    pub session_id: u32,
+    pub admin: Address,
+}
+
+/// Emitted by `concede`; `winner` is the conceding player's opponent.
+#[contractevent]
+pub struct Conceded {
+    #[topic]
+    pub session_id: u32,
+    pub player: Address,
+    pub winner: Address,
+}
+
+/// Emitted by `replace_commitment` with the player's new commitment.
+#[contractevent]
+pub struct CommitmentReplaced {
+    #[topic]
+    pub session_id: u32,
+    pub player: Address,
+    pub commitment: BytesN<32>,
+}
+
+/// Emitted by `dispute` when the operator overrides a pending result.
+#[contractevent]
+pub struct Disputed {
+    #[topic]
+    pub session_id: u32,
+    pub operator: Address,
+    pub player1_won: bool,
+}
+
+/// Emitted by `finalize` once a held-back result has been reported to the hub.
+#[contractevent]
+pub struct SettlementFinalized {
+    #[topic]
+    pub session_id: u32,
+    pub player1_won: Option<bool>,
+}
+
+/// Emitted by `block_address` (`block`) and `unblock_address` (`unblock`).
+#[contractevent]
+pub struct Moderated {
+    #[topic]
+    pub subject: Address,
+    pub action: Symbol,
+    pub reason: u32,
+    pub actor: Address,
+}
+
+/// Emitted by `migrate` once the new code has confirmed its version.
+#[contractevent]
+pub struct VersionChanged {
+    pub old_version: u32,
+    pub new_version: u32,
+}
+
+// Version of this build's code, recorded under DataKey::CodeVersion; upgrades may not go backwards.
+// Clients must move with it when it changes the interface:
+//   2: commit_number, reveal_number, choose_role and choose_role_and_commit return GamePublicView.
+//   3: GamePublicView carries the players' display names.
+//   4: GamePublicView carries `label` and `organizer`.
+//   5: Config and InitConfig carry `reservation_ledgers`.
+//   6: Deprecated carries `announcement`.
+//   7: Config and InitConfig carry `dispute_window_ledgers`.
+//   8: Drawn games; the hub must implement `end_game_draw`, and PendingSettlement and SettlementFinalized carry
+//      `player1_won` as an Option.
+//   9: Config and InitConfig carry `timeout_ledgers`.
+//  10: The hub must implement `cancel_game`.
+//  11: Keys set with `set_verification_key` take Groth16 proof blobs.
+//  12: Config and InitConfig carry `verifier`.
+//  13: Salted commitments, Groth16 proofs and `Verifier::verify` bind the `reveal_context`.
+//  14: Reveals that don't decode fail with `ProofMalformed` rather than `ProofInvalid`.
+//  15: GamePublicView carries `balls_remaining`.
+//  16: level chases go to a super over (Phase::SuperOver, innings 3 and 4) unless `super_over_balls` is 0;
+//      GamePublicView carries the super-over fields, Config and InitConfig `super_over_balls`.
+//  17: GamePublicView carries `min_number` and `max_number`.
+//  18: Config and InitConfig carry `open_game_ledgers`.
+//  19: `set_admin` and AdminOp::SetAdmin give way to `propose_admin` / `accept_admin` (AdminOp::ProposeAdmin).
+//  20: Config and InitConfig carry `game_ttl_ledgers`.
+//  21: `migrate` takes `from_version`; game mutators fail with `MigrationPending` until it has run.
+//  22: starts refuse negative stakes; Config carries `stake_limits`, InitConfig `min_stake_points` and
+//      `max_stake_points`.
+//  23: Config and InitConfig carry `require_equal_stakes`.
+//  24: the hub is given stakes net of `fee_bps`; Config and InitConfig carry `fee_bps`.
+//  25: the constructor takes the hub as an Option and `get_hub` and Config return it as one.
+//  26: Results go to the hub through `end_game_detailed` unless `legacy_hub` is set; Config and InitConfig carry it.
+//  27: Config and InitConfig carry `hub_supports_cancel`.
+//  28: Config and InitConfig carry `direct_start_allowed`.
+//  29: staked starts can name an allowed hub, and results and cancels go to the hub the game was opened on.
+//  30: a failed hub report no longer reverts the move, and `report_result` retries it.
+//  31: `start_game` returns the GamePublicView of the game it opened.
+//  32: Game carries everything added since it shipped: `vk_version`, the phase and action ledgers, `rounds`, `label`
+//      and `organizer`, the ball cap, super-over fields, number range, `handicap`, `is_practice`, `voided`,
+//      `escrow_token`, `friendly`, `hub`, `result_reported`, `p1_key` and `p2_key`.
+pub const CONTRACT_VERSION: u32 = 32;
+
+// Bump whenever a persisted type's XDR layout changes; the golden tests in test.rs enforce it.
+pub const STORAGE_SCHEMA_VERSION: u32 = 4;
+
+// Game entries live about 30 days past their last write unless the admin sets `set_game_ttl`.
+const GAME_TTL_LEDGERS: u32 = 518_400;
+const MIN_GAME_TTL_LEDGERS: u32 = 17_280;
+const MAX_GAME_TTL_LEDGERS: u32 = 1_036_800;
+const DEFAULT_ROLLBACK_WINDOW_LEDGERS: u32 = 17_280;
+const PERSISTENT_TTL_LEDGERS: u32 = 3_110_400;
+// Most recent revisions kept by `get_version_history`.
+const VERSION_HISTORY_LEN: u32 = 16;
+const MAX_DELEGATIONS: u32 = 16;
+// Session ids one `get_games` or `cleanup_games` call may take.
+const MAX_BATCH_READ: u32 = 25;
+// Leaves one `commit_merkle` root may hold.
+const MAX_MERKLE_LEAVES: u32 = 64;
+// Unfinished games per player; see `get_games_for_player`.
+const MAX_ACTIVE_GAMES: u32 = 50;
+// A best-of-9.
+#[cfg(feature = "tournaments")]
+const MAX_SERIES_WINS: u32 = 5;
+// Longest display name, in characters.
+const MAX_NAME_LEN: u32 = 20;
+const MAX_LABEL_LEN: u32 = 24;
+// Reported by `get_metadata` whatever the deployment's branding says.
+const CONTRACT_NAME: &str = "hand_cricket";
+// Reservations last about a week unless the admin configures otherwise.
+const DEFAULT_RESERVATION_LEDGERS: u32 = 120_960;
+const MAX_RESERVATION_SIZE: u32 = 1_024;
+const MAX_RESERVATIONS: u32 = 64;
+// `reserve_session` hands out ids from the third quarter of the range, counting up, and holds each for about an hour.
+const FIRST_RESERVED_SESSION: u32 = 1 << 31;
+// `start_game_auto` counts up from here; explicit starts can't use an id it hasn't handed out yet.
+const FIRST_AUTO_SESSION: u32 = 3 << 30;
+const CREATOR_RESERVATION_LEDGERS: u32 = 720;
+// A challenge can be accepted for about a day.
+const CHALLENGE_LEDGERS: u32 = 17_280;
+// Open games wait about an hour for a joiner unless the admin configures otherwise.
+const DEFAULT_OPEN_GAME_LEDGERS: u32 = 720;
+const MAX_OPEN_GAMES: u32 = 50;
+// 5%.
+const MAX_FEE_BPS: u32 = 500;
+// An announcement reads as absent once this many ledgers (about a day) have passed since its start.
+const ANNOUNCEMENT_DURATION_LEDGERS: u32 = 17_280;
+// How long a player may stall on their move before the opponent can claim the game (about a day).
+const DEFAULT_TIMEOUT_LEDGERS: u32 = 17_280;
+const DEFAULT_SUPER_OVER_BALLS: u32 = 1;
+const MAX_SUPER_OVER_BALLS: u32 = 6;
+// Moderation logs keep the newest entries and are read in pages, oldest first.
+const MAX_MODERATION_LOG: u32 = 50;
+const MODERATION_PAGE_SIZE: u32 = 10;
+// Emote codes run from 1 to MAX_EMOTE; clients map them to pictures.
+const MAX_EMOTE: u32 = 16;
+
+// Transcript layout for `settle_transcript` (all u32 big-endian):
+// [p1 toss][p2 toss][toss winner bats 0|1][ball count] then per ball [index from 0][p1 number][p2 number],
+// then [claimed winner 1|2, or 0 for a draw].
+const TRANSCRIPT_HEADER_LEN: u32 = 16;
+const TRANSCRIPT_BALL_LEN: u32 = 12;
+// The standard hand; `start_configured_game` may pick another range up to MAX_NUMBER_CAP.
+const MIN_NUMBER: u32 = 1;
+const MAX_NUMBER: u32 = 6;
+const MAX_NUMBER_CAP: u32 = 20;
+
+// ----------------------------------------------------------------------------
+// Reveal verification. The `zk` feature checks a proof blob against versioned
+// verifying keys; without it a reveal opens the commitment directly.
+// ----------------------------------------------------------------------------
+
+// Proof blob layout: [vk_version u32][num_inputs u32][commitment 32][number field 32], then by input count
+//   2: [proof 64], opaque bytes checked against keys from `add_verifying_key`;
+//   3: [context 32][Groth16 proof], for keys set with `set_verification_key`.
+#[cfg(feature = "zk")]
+const PROOF_HEADER_LEN: u32 = 72;
+#[cfg(feature = "zk")]
+const OPAQUE_PROOF_LEN: u32 = 64;
+// Longer blobs are rejected before any field is read.
+#[cfg(feature = "zk")]
+const MAX_PROOF_BLOB_LEN: u32 = 512;
+
+fn read_u32_be(blob: &Bytes, offset: u32) -> u32 {
+    let mut buf = [0u8; 4];
+    blob.slice(offset..offset + 4).copy_into_slice(&mut buf);
+    u32::from_be_bytes(buf)
+}
+
+/// A proof blob split into its fields; see `ProofData::try_from_bytes` for the layout it accepts.
+#[cfg(feature = "zk")]
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProofData {
+    // Verifying key version the proof is for.
+    pub version: u32,
+    pub commitment: BytesN<32>,
+    pub number: u32,
+    // Present in three-input blobs only.
+    pub context: Option<BytesN<32>>,
+    pub proof_points: Bytes,
+}
+
+#[cfg(feature = "zk")]
+impl ProofData {
+    // ProofMalformed for anything that isn't exactly one of the two layouts, including a number field
+    // that isn't canonical (28 zero bytes then the u32). Whether the proof holds is checked separately.
+    pub fn try_from_bytes(env: &Env, blob: &Bytes) -> Result<ProofData, Error> {
+        if blob.len() < PROOF_HEADER_LEN || blob.len() > MAX_PROOF_BLOB_LEN { return Err(Error::ProofMalformed); }
+        let (context, points_at) = match read_u32_be(blob, 4) {
+            2 => (None, PROOF_HEADER_LEN),
+            3 if blob.len() >= PROOF_HEADER_LEN + 32 => (Some(bytes_n::<32>(blob, PROOF_HEADER_LEN)), PROOF_HEADER_LEN + 32),
+            _ => return Err(Error::ProofMalformed),
+        };
+        let expected_points = if context.is_some() { groth16::PROOF_LEN } else { OPAQUE_PROOF_LEN };
+        if blob.len() != points_at + expected_points { return Err(Error::ProofMalformed); }
+        if blob.slice(40..68) != Bytes::from_array(env, &[0u8; 28]) { return Err(Error::ProofMalformed); }
+        Ok(ProofData {
+            version: read_u32_be(blob, 0),
+            commitment: bytes_n::<32>(blob, 8),
+            number: read_u32_be(blob, 68),
+            context,
+            proof_points: blob.slice(points_at..),
+        })
+    }
+
+    // The blob's claimed inputs are the game's, under a key version the game accepts.
+    fn claims(&self, env: &Env, pinned: Option<u32>, stored_commitment: &BytesN<32>, number: u32) -> bool {
+        self.number == number && self.commitment == *stored_commitment && vk_accepted(env, pinned, self.version)
+    }
+}
+
+#[cfg(feature = "zk")]
+fn bytes_n<const N: usize>(blob: &Bytes, offset: u32) -> BytesN<N> {
+    blob.slice(offset..offset + N as u32).try_into().unwrap()
+}
+
+// A game pinned to a version only accepts that version (even once retired); unpinned games accept any active one.
+#[cfg(feature = "zk")]
+fn vk_accepted(env: &Env, pinned: Option<u32>, version: u32) -> bool {
+    match pinned {
+        Some(v) => v == version,
+        None => active_vk_versions(env).contains(version),
+    }
+}
+
+#[cfg(feature = "zk")]
+fn active_vk_versions(env: &Env) -> Vec<u32> {
+    env.storage().instance().get(&DataKey::ActiveVkVersions).unwrap_or(Vec::new(env))
+}
+
+// What `check_reveal_proof` accepts for an opaque key, straight from the blob; the unit and fuzz tests drive it.
+#[cfg(all(test, feature = "zk"))]
+fn verify_proof(env: &Env, vk_version: Option<u32>, stored_commitment: &BytesN<32>, number: u32, proof_blob: &Bytes) -> bool {
+    ProofData::try_from_bytes(env, proof_blob).is_ok_and(|proof| proof.context.is_none() && proof.claims(env, vk_version, stored_commitment, number))
+}
+
+#[cfg(feature = "zk")]
+type RevealProof = ProofData;
+
+#[cfg(feature = "zk")]
+fn decode_reveal(env: &Env, proof_blob: &Bytes) -> Result<ProofData, Error> {
+    ProofData::try_from_bytes(env, proof_blob)
+}
+
+// Picks the check for the key the proof names: opaque keys from `add_verifying_key` need a two-input
+// blob, Groth16 keys a proof that verifies for the stored commitment in the round being revealed.
+#[cfg(feature = "zk")]
+fn check_reveal_proof(env: &Env, vk_version: Option<u32>, stored_commitment: &BytesN<32>, number: u32, context: &BytesN<32>, proof: &ProofData) -> bool {
+    let Some(vk) = env.storage().persistent().get::<_, VerifyingKey>(&DataKey::VerifyingKey(proof.version)) else { return false };
+    if !proof.claims(env, vk_version, stored_commitment, number) { return false; }
+    match &proof.context {
+        None => !groth16::is_groth16_key(&vk.key),
+        Some(claimed) => claimed == context
+            && groth16::is_groth16_key(&vk.key)
+            && groth16::verify(env, &vk.key, stored_commitment, number, context, &proof.proof_points),
+    }
+}
+
+// The reveal is the 32-byte salt. It binds no context; `reveal_with_salt` is the bound reveal for these builds.
+#[cfg(not(feature = "zk"))]
+type RevealProof = BytesN<32>;
+
+#[cfg(not(feature = "zk"))]
+fn decode_reveal(_env: &Env, proof_blob: &Bytes) -> Result<BytesN<32>, Error> {
+    proof_blob.clone().try_into().map_err(|_| Error::ProofMalformed)
+}
+
+#[cfg(not(feature = "zk"))]
+fn check_reveal_proof(env: &Env, _vk_version: Option<u32>, stored_commitment: &BytesN<32>, number: u32, _context: &BytesN<32>, salt: &BytesN<32>) -> bool {
+    verify_proof(env, stored_commitment, number, salt)
+}
+
+// keccak256(number BE || salt) must equal the commitment.
+#[cfg(not(feature = "zk"))]
+fn verify_proof(env: &Env, stored_commitment: &BytesN<32>, number: u32, salt: &BytesN<32>) -> bool {
+    let mut preimage = Bytes::from_array(env, &number.to_be_bytes());
+    preimage.append(salt.as_bytes());
+    env.crypto().keccak256(&preimage).to_array() == stored_commitment.to_array()
+}
+
+// The round a reveal belongs to, so a commitment or proof made for one ball, game or player opens nowhere else:
+// keccak256(session_id u32 BE || innings u32 BE || round u32 BE || keccak256(player strkey)). `innings` and
+// `round` are `Game::innings` and `Game::rounds` when the reveal lands (round 0 is the toss, 1 the first ball).
+pub fn reveal_context(env: &Env, session_id: u32, innings: u32, round: u32, player: &Address) -> BytesN<32> {
+    let mut context = Bytes::from_array(env, &session_id.to_be_bytes());
+    context.append(&Bytes::from_array(env, &innings.to_be_bytes()));
+    context.append(&Bytes::from_array(env, &round.to_be_bytes()));
+    context.append(&env.crypto().keccak256(&player.to_string().to_bytes()).into());
+    env.crypto().keccak256(&context).into()
+}
+
+// What both players sign for one `settle_signed_balls` move: keccak256(contract strkey) || session_id u32 BE ||
+// ball_index u32 BE || p1_number u32 BE || p2_number u32 BE.
+pub fn signed_ball_message(env: &Env, session_id: u32, ball_index: u32, p1_number: u32, p2_number: u32) -> Bytes {
+    let mut message: Bytes = env.crypto().keccak256(&env.current_contract_address().to_string().to_bytes()).into();
+    for value in [session_id, ball_index, p1_number, p2_number] { message.append(&Bytes::from_array(env, &value.to_be_bytes())); }
+    message
+}
+
+// Commitment opened by `reveal_with_salt`: keccak256(number u32 BE || salt 32 || reveal_context).
+pub fn salted_commitment(env: &Env, context: &BytesN<32>, number: u32, salt: &BytesN<32>) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &number.to_be_bytes());
+    preimage.append(salt.as_bytes());
+    preimage.append(context.as_bytes());
+    env.crypto().keccak256(&preimage).into()
+}
+
+// Leaf of a `commit_merkle` tree: keccak256(number u32 BE || salt 32 || index u32 BE), `index` counting the
+// root's leaves from 0.
+pub fn merkle_leaf(env: &Env, number: u32, salt: &BytesN<32>, index: u32) -> BytesN<32> {
+    let mut preimage = Bytes::from_array(env, &number.to_be_bytes());
+    preimage.append(salt.as_bytes());
+    preimage.append(&Bytes::from_array(env, &index.to_be_bytes()));
+    env.crypto().keccak256(&preimage).into()
+}
+
+// The root `path` leads to from the leaf at `index`. Each level hashes the node with its sibling, the node first
+// where that level's bit of `index` is 0. Trees are padded to a power of two, so a path is `merkle_depth` long.
+pub fn merkle_root(env: &Env, leaf: BytesN<32>, index: u32, path: &Vec<BytesN<32>>) -> BytesN<32> {
+    let mut node = leaf;
+    for (level, sibling) in path.iter().enumerate() {
+        let (left, right) = if (index >> level) & 1 == 0 { (node, sibling) } else { (sibling, node) };
+        let mut pair = Bytes::from_array(env, &left.to_array());
+        pair.append(right.as_bytes());
+        node = env.crypto().keccak256(&pair).into();
+    }
+    node
+}
+
+pub fn merkle_depth(leaf_count: u32) -> u32 {
+    leaf_count.next_power_of_two().trailing_zeros()
+}
+
+// Games written before StoredGame existed are a bare Game (version 0); that layout is identical to V1.
+pub(crate) fn decode_game(env: &Env, raw: &Val) -> (Game, bool) {
+    match StoredGame::try_from_val(env, raw) {
+        Ok(stored) => upgrade_stored_game(env, stored),
+        Err(_) => (upgrade_v1(env, GameV1::try_from_val(env, raw).expect("unreadable game entry")), true),
+    }
+}
+
+fn upgrade_stored_game(env: &Env, stored: StoredGame) -> (Game, bool) {
+    match stored {
+        StoredGame::V1(game) => (upgrade_v1(env, game), true),
+        StoredGame::V2(game) => (game, false),
+    }
+}
+
+// V1 games predate everything else a game records. The toss is the only round they show, their clocks start
+// at the upgrade, they aren't pinned to a verifying key, and they were staked through the configured hub with
+// the standard hands and no options. A finished one had already told the hub its result.
+fn upgrade_v1(env: &Env, game: GameV1) -> Game {
+    let now = env.ledger().sequence();
+    Game {
+        rounds: if game.toss_winner.is_some() { 1 } else { 0 },
+        result_reported: game.phase == Phase::Finished,
+        player1: game.player1, player2: game.player2,
+        player1_points: game.player1_points, player2_points: game.player2_points,
+        player1_is_odd: game.player1_is_odd, toss_winner: game.toss_winner, batter: game.batter,
+        p1_commitment: game.p1_commitment, p2_commitment: game.p2_commitment,
+        p1_number: game.p1_number, p2_number: game.p2_number,
+        p1_score: game.p1_score, p2_score: game.p2_score, innings: game.innings, target: game.target,
+        phase: game.phase, winner: game.winner, vk_version: None,
+        phase_started_ledger: now, last_action_ledger: now,
+        label: None, organizer: None,
+        max_balls_per_innings: 0, balls_bowled: 0,
+        super_over: 0, super_over_balls: 0, p1_super_score: 0, p2_super_score: 0,
+        min_number: MIN_NUMBER, max_number: MAX_NUMBER,
+        handicap: 0,
+        is_practice: false,
+        voided: false,
+        escrow_token: None,
+        friendly: false,
+        hub: None,
+        p1_key: None, p2_key: None,
+    }
+}
+
+// A Symbol's XDR is [type u32][length u32][chars], so its length can be read without std.
+fn symbol_len(env: &Env, symbol: &Symbol) -> u32 {
+    read_u32_be(&symbol.clone().to_xdr(env), 4)
+}
+
+// Returns the game in the current layout and whether it was upgraded; only `write_game` persists that.
+fn read_game(env: &Env, session_id: u32) -> Result<(Game, bool), Error> {
+    let raw: Val = env.storage().temporary().get(&DataKey::Game(session_id)).ok_or(Error::GameNotFound)?;
+    Ok(decode_game(env, &raw))
+}
+
+// Upgrade bookkeeping, split from the wasm swap so it can be exercised without real wasm.
+pub(crate) fn record_upgrade(env: &Env, new_wasm_hash: &BytesN<32>, new_version: u32) {
+    let storage = env.storage().instance();
+    match storage.get::<_, BytesN<32>>(&DataKey::CurrentWasmHash) {
+        Some(current) => storage.set(&DataKey::PreviousWasmHash, &current),
+        None => storage.remove(&DataKey::PreviousWasmHash),
+    }
+    storage.set(&DataKey::PreviousCodeVersion, &storage.get::<_, u32>(&DataKey::CodeVersion).unwrap_or(0));
+    storage.set(&DataKey::CurrentWasmHash, new_wasm_hash);
+    storage.set(&DataKey::PendingCodeVersion, &new_version);
+    storage.set(&DataKey::UpgradedAtLedger, &env.ledger().sequence());
+}
+
+// Swaps current and previous records and returns the hash to re-apply.
+pub(crate) fn record_rollback(env: &Env) -> Result<BytesN<32>, Error> {
+    let storage = env.storage().instance();
+    let previous: BytesN<32> = storage.get(&DataKey::PreviousWasmHash).ok_or(Error::NoRollbackTarget)?;
+    let upgraded_at: u32 = storage.get(&DataKey::UpgradedAtLedger).unwrap_or(0);
+    let window: u32 = storage.get(&DataKey::RollbackWindow).unwrap_or(DEFAULT_ROLLBACK_WINDOW_LEDGERS);
+    if env.ledger().sequence() > upgraded_at.saturating_add(window) { return Err(Error::RollbackWindowClosed); }
+
+    let current: BytesN<32> = storage.get(&DataKey::CurrentWasmHash).ok_or(Error::NoRollbackTarget)?;
+    // The version being left is the one announced if migrate hasn't confirmed it yet.
+    let leaving = storage.get(&DataKey::PendingCodeVersion).unwrap_or(storage.get(&DataKey::CodeVersion).unwrap_or(0u32));
+    let restored: u32 = storage.get(&DataKey::PreviousCodeVersion).unwrap_or(0);
+    storage.set(&DataKey::CurrentWasmHash, &previous);
+    storage.set(&DataKey::PreviousWasmHash, &current);
+    storage.set(&DataKey::CodeVersion, &restored);
+    storage.set(&DataKey::PreviousCodeVersion, &leaving);
+    storage.remove(&DataKey::PendingCodeVersion);
+    storage.set(&DataKey::UpgradedAtLedger, &env.ledger().sequence());
+    append_version_record(env, restored, &previous);
+    Ok(previous)
+}
+
+// Only `migrate` and `record_rollback` write the history: a revision is recorded once its code is live.
+fn append_version_record(env: &Env, version: u32, wasm_hash: &BytesN<32>) {
+    let key = DataKey::VersionHistory;
+    let mut history: Vec<VersionRecord> = env.storage().persistent().get(&key).unwrap_or(Vec::new(env));
+    history.push_back(VersionRecord { version, wasm_hash: wasm_hash.clone(), ledger: env.ledger().sequence() });
+    while history.len() > VERSION_HISTORY_LEN { history.pop_front(); }
+    env.storage().persistent().set(&key, &history);
+    env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+pub fn migration_blob_hash(env: &Env, blob: &MigrationBlob) -> BytesN<32> {
+    env.crypto().keccak256(&blob.clone().to_xdr(env)).into()
+}
+
+// Compiled-in features minus those the operator has switched off; unset means everything built in is on.
+fn enabled_features(env: &Env) -> u32 {
+    let toggles: u32 = env.storage().instance().get(&DataKey::FeatureToggles).unwrap_or(features::FEATURES);
+    features::FEATURES & (toggles | !features::TOGGLEABLE)
+}
+
+fn require_feature(env: &Env, bit: u32) -> Result<(), Error> {
+    if enabled_features(env) & bit == 0 { return Err(Error::FeatureDisabled); }
+    Ok(())
+}
+
+fn delegations(env: &Env, player: &Address) -> Vec<Delegation> {
+    env.storage().persistent().get(&DataKey::Delegations(player.clone())).unwrap_or(Vec::new(env))
+}
+
+// While a delegation covers the session, its delegate signs moves instead of the player; a
+// session-specific delegation takes precedence over one for all sessions.
+fn require_move_auth(env: &Env, session_id: u32, player: &Address) {
+    move_signer(env, session_id, player).require_auth();
+}
+
+fn move_signer(env: &Env, session_id: u32, player: &Address) -> Address {
+    let delegations = delegations(env, player);
+    let for_session = delegations.iter().find(|d| d.session_id == Some(session_id));
+    match for_session.or_else(|| delegations.iter().find(|d| d.session_id.is_none())) {
+        Some(delegation) => delegation.delegate,
+        None => player.clone(),
+    }
+}
+
+fn active_reservations(env: &Env) -> Vec<Reservation> {
+    let reservations: Vec<Reservation> = env.storage().instance().get(&DataKey::Reservations).unwrap_or(Vec::new(env));
+    let now = env.ledger().sequence();
+    let mut active = Vec::new(env);
+    for r in reservations.iter().filter(|r| r.expires_ledger > now) { active.push_back(r); }
+    active
+}
+
+fn watcher_count(env: &Env, subject: &Address) -> u32 {
+    env.storage().persistent().get(&DataKey::Watchers(subject.clone())).unwrap_or(0)
+}
+
+fn player_games(env: &Env, player: &Address) -> Vec<u32> {
+    env.storage().persistent().get(&DataKey::PlayerGames(player.clone())).unwrap_or(Vec::new(env))
+}
+
+fn write_player_games(env: &Env, player: &Address, games: &Vec<u32>) {
+    let key = DataKey::PlayerGames(player.clone());
+    if games.is_empty() { env.storage().persistent().remove(&key); return; }
+    env.storage().persistent().set(&key, games);
+    env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+// A full index first drops sessions whose game has expired. Opted-out players aren't indexed.
+fn index_game(env: &Env, session_id: u32, player: &Address) -> Result<(), Error> {
+    if env.storage().persistent().has(&DataKey::StatsOptOut(player.clone())) { return Ok(()); }
+    let mut games = player_games(env, player);
+    if games.len() >= MAX_ACTIVE_GAMES { games = live_sessions(env, &games); }
+    if games.len() >= MAX_ACTIVE_GAMES { return Err(Error::TooManyActiveGames); }
+    games.push_back(session_id);
+    write_player_games(env, player, &games);
+    Ok(())
+}
+
+// Whether `index_game` would take another game for `player`.
+#[cfg(feature = "tournaments")]
+fn index_has_room(env: &Env, player: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::StatsOptOut(player.clone()))
+        || live_sessions(env, &player_games(env, player)).len() < MAX_ACTIVE_GAMES
+}
+
+fn live_sessions(env: &Env, ids: &Vec<u32>) -> Vec<u32> {
+    let mut live = Vec::new(env);
+    for id in ids.iter().filter(|id| env.storage().temporary().has(&DataKey::Game(*id))) { live.push_back(id); }
+    live
+}
+
+fn unindex_game(env: &Env, session_id: u32, game: &Game) {
+    for player in [&game.player1, &game.player2] {
+        let mut games = player_games(env, player);
+        if let Some(i) = games.first_index_of(session_id) {
+            games.remove(i);
+            write_player_games(env, player, &games);
+        }
+    }
+}
+
+fn publish_watched(env: &Env, session_id: u32, game: &Game, kind: Symbol) {
+    for subject in [&game.player1, &game.player2] {
+        if watcher_count(env, subject) > 0 { Watched { subject: subject.clone(), session_id, kind: kind.clone() }.publish(env); }
+    }
+}
+
+fn check_number(game: &Game, number: u32) -> Result<(), Error> {
+    if (game.min_number..=game.max_number).contains(&number) { Ok(()) } else { Err(Error::NumberOutOfRange) }
+}
+
+fn is_blocked(env: &Env, addr: &Address) -> bool {
+    env.storage().persistent().has(&DataKey::Blocked(addr.clone()))
+}
+
+fn moderation_log(env: &Env, addr: &Address) -> Vec<ModerationEntry> {
+    env.storage().persistent().get(&DataKey::ModerationLog(addr.clone())).unwrap_or_else(|| Vec::new(env))
+}
+
+fn creator_reservation(env: &Env, session_id: u32) -> Option<CreatorReservation> {
+    let hold: CreatorReservation = env.storage().temporary().get(&DataKey::CreatorReservation(session_id))?;
+    if hold.expires_ledger > env.ledger().sequence() { Some(hold) } else { None }
+}
+
+fn open_game(env: &Env, session_id: u32) -> Option<OpenGame> {
+    let open: OpenGame = env.storage().temporary().get(&DataKey::OpenGame(session_id))?;
+    if open.expires_ledger > env.ledger().sequence() { Some(open) } else { None }
+}
+
+fn open_game_index(env: &Env) -> Vec<u32> {
+    env.storage().instance().get(&DataKey::OpenGames).unwrap_or(Vec::new(env))
+}
+
+// Reserved ids wrap before they reach the auto-allocated quarter.
+fn next_reserved_session(session_id: u32) -> u32 {
+    let next = session_id.saturating_add(1);
+    if (FIRST_RESERVED_SESSION..FIRST_AUTO_SESSION).contains(&next) { next } else { FIRST_RESERVED_SESSION }
+}
+
+// Kept as a u64 so it can count past the last id.
+fn next_auto_session(env: &Env) -> u64 {
+    env.storage().instance().get(&DataKey::NextSession).unwrap_or(FIRST_AUTO_SESSION as u64)
+}
+
+fn assert_not_retired(env: &Env) {
+    if env.storage().instance().has(&DataKey::Retired) { panic_with_error!(env, Error::Retired); }
+}
+
+// Between `upgrade` and `migrate` the new code may find data it hasn't confirmed it can handle, so games stay put.
+fn ensure_migrated(env: &Env) -> Result<(), Error> {
+    if env.storage().instance().has(&DataKey::PendingCodeVersion) { return Err(Error::MigrationPending); }
+    Ok(())
+}
+
+// Pays out an escrowed game: the pot to the winner, or each deposit back to its player when there is none.
+#[cfg(feature = "escrow")]
+fn release_escrow(env: &Env, game: &Game, player1_won: Option<bool>) {
+    let Some(token) = &game.escrow_token else { return };
+    let token_client = token::TokenClient::new(env, token);
+    let vault = env.current_contract_address();
+    match player1_won {
+        Some(true) => token_client.transfer(&vault, &game.player1, &(game.player1_points + game.player2_points)),
+        Some(false) => token_client.transfer(&vault, &game.player2, &(game.player1_points + game.player2_points)),
+        None => {
+            token_client.transfer(&vault, &game.player1, &game.player1_points);
+            token_client.transfer(&vault, &game.player2, &game.player2_points);
+        }
+    }
+}
+
+// Builds without `escrow` never start an escrowed game.
+#[cfg(not(feature = "escrow"))]
+fn release_escrow(_env: &Env, _game: &Game, _player1_won: Option<bool>) {}
+
+// The fee on a stake, rounded down so any fraction stays with the player. Split so a huge stake can't overflow.
+fn fee_for(points: i128, bps: u32) -> i128 {
+    let bps = bps as i128;
+    points / 10_000 * bps + points % 10_000 * bps / 10_000
+}
+
+// Innings begun, the one in progress included and each super-over half counted as one; 0 if the game ended
+// before anyone batted.
+fn innings_played(game: &Game) -> u32 {
+    if game.batter.is_none() { return 0; }
+    if game.super_over == 0 { game.innings } else { 2 * game.super_over + game.innings - 2 }
+}
+
+fn game_ttl(env: &Env) -> u32 { env.storage().instance().get(&DataKey::GameTtl).unwrap_or(GAME_TTL_LEDGERS) }
+
+// extend_ttl only ever lengthens, so entries written under a longer TTL keep it when the admin lowers it.
+fn extend_with_game_ttl(env: &Env, key: &DataKey) {
+    let ttl = game_ttl(env);
+    env.storage().temporary().extend_ttl(key, ttl, ttl);
+}
+
+fn write_game(env: &Env, session_id: u32, game: &Game) {
+    let key = DataKey::Game(session_id);
+    env.storage().temporary().set(&key, &StoredGame::V2(game.clone()));
+    extend_with_game_ttl(env, &key);
+}
+
+// A game about to start: toss not yet committed to, no label and no ball limit.
+fn new_game(env: &Env, player1: Address, player2: Address, player1_points: i128, player2_points: i128, player1_is_odd: bool, vk_version: Option<u32>) -> Game {
+    Game {
+        player1, player2, player1_points, player2_points,
+        player1_is_odd, toss_winner: None, batter: None,
+        p1_commitment: None, p2_commitment: None,
+        p1_number: None, p2_number: None,
+        p1_score: 0, p2_score: 0, innings: 1, target: 0,
+        phase: Phase::TossCommit, winner: None, vk_version,
+        phase_started_ledger: env.ledger().sequence(),
+        last_action_ledger: env.ledger().sequence(),
+        rounds: 0, label: None, organizer: None,
+        max_balls_per_innings: 0, balls_bowled: 0,
+        super_over: 0, super_over_balls: 0, p1_super_score: 0, p2_super_score: 0,
+        min_number: MIN_NUMBER, max_number: MAX_NUMBER,
+        handicap: 0,
+        is_practice: false,
+        voided: false,
+        escrow_token: None,
+        friendly: false,
+        hub: None,
+        result_reported: false,
+        p1_key: None, p2_key: None,
+    }
+}
+
+#[cfg(feature = "tournaments")]
+fn write_series(env: &Env, series_id: u32, series: &Series) {
+    let key = DataKey::Series(series_id);
+    env.storage().temporary().set(&key, series);
+    extend_with_game_ttl(env, &key);
+}
+
+fn game_result(game: &Game, finished_ledger: u32) -> GameResult {
+    GameResult {
+        player1: game.player1.clone(),
+        player2: game.player2.clone(),
+        winner: game.winner.clone(),
+        p1_score: game.p1_score,
+        p2_score: game.p2_score,
+        target: game.target,
+        finished_ledger,
+    }
+}
+
+fn write_result(env: &Env, session_id: u32, result: &GameResult) {
+    let key = DataKey::Result(session_id);
+    env.storage().persistent().set(&key, result);
+    env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+// Toss parity: keccak256(session_id BE || player1 strkey || player2 strkey); an even last byte makes player1 odd.
+pub fn toss_player1_is_odd(env: &Env, session_id: u32, player1: &Address, player2: &Address) -> bool {
+    let mut seed_bytes = Bytes::from_array(env, &session_id.to_be_bytes());
+    seed_bytes.append(&player1.to_string().to_bytes());
+    seed_bytes.append(&player2.to_string().to_bytes());
+    env.crypto().keccak256(&seed_bytes).to_array()[31].is_multiple_of(2)
+}
+
+pub fn toss_player1_wins(player1_is_odd: bool, p1_number: u32, p2_number: u32) -> bool {
+    let sum_is_odd = !(p1_number + p2_number).is_multiple_of(2);
+    player1_is_odd == sum_is_odd
+}
+
+/// What one ball does, from the batting side's point of view.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BallOutcome {
+    /// Batter scores; carries the new score.
+    Runs(u32),
+    /// Out in the first innings; the other side chases `target`.
+    InningsBreak { target: u32 },
+    /// Second-innings batter reached the target; carries the final score.
+    ChaseComplete(u32),
+    /// Out in the second innings more than one run short of the target.
+    ChaseFailed,
+    /// Out in the second innings one run short of the target, level with the first innings.
+    ChaseTied,
+}
+
+// Pure ball resolution shared by the contract and the conformance vectors.
+pub fn ball_outcome(innings: u32, target: u32, batter_score: u32, batter_number: u32, bowler_number: u32) -> BallOutcome {
+    if batter_number == bowler_number {
+        if innings == 1 { return BallOutcome::InningsBreak { target: batter_score + 1 }; }
+        return if batter_score + 1 == target { BallOutcome::ChaseTied } else { BallOutcome::ChaseFailed };
+    }
+    let score = batter_score.saturating_add(batter_number);
+    if innings == 2 && score >= target { BallOutcome::ChaseComplete(score) } else { BallOutcome::Runs(score) }
+}
+
+pub fn public_view(env: &Env, session_id: u32, game: &Game) -> GamePublicView {
+    GamePublicView {
+        session_id,
+        player1: game.player1.clone(),
+        player2: game.player2.clone(),
+        player1_points: game.player1_points,
+        player2_points: game.player2_points,
+        player1_is_odd: game.player1_is_odd,
+        toss_winner: game.toss_winner.clone(),
+        batter: game.batter.clone(),
+        p1_committed: game.p1_commitment.is_some(),
+        p2_committed: game.p2_commitment.is_some(),
+        p1_revealed: game.p1_number.is_some(),
+        p2_revealed: game.p2_number.is_some(),
+        p1_score: game.p1_score,
+        p2_score: game.p2_score,
+        innings: game.innings,
+        target: game.target,
+        phase: game.phase.clone(),
+        winner: game.winner.clone(),
+        phase_started_ledger: game.phase_started_ledger,
+        p1_name: display_name(env, &game.player1),
+        p2_name: display_name(env, &game.player2),
+        label: game.label.clone(),
+        organizer: game.organizer.clone(),
+        balls_remaining: (ball_limit(game) > 0).then(|| ball_limit(game).saturating_sub(game.balls_bowled)),
+        super_over: game.super_over,
+        p1_super_score: game.p1_super_score,
+        p2_super_score: game.p2_super_score,
+        min_number: game.min_number,
+        max_number: game.max_number,
+    }
+}
+
+// `target` moved by the handicap while the first batter is still in: up when they get the bonus, down when the chaser does.
+fn handicapped_target(game: &Game, target: u32) -> u32 {
+    let p1_batting = game.batter.as_ref() == Some(&game.player1);
+    let bonus = if p1_batting { game.handicap as i64 } else { -(game.handicap as i64) };
+    (target as i64 + bonus).clamp(1, u32::MAX as i64) as u32
+}
+
+// Balls allowed in the current innings, 0 for no limit.
+fn ball_limit(game: &Game) -> u32 {
+    if game.innings > 2 { game.super_over_balls } else { game.max_balls_per_innings }
+}
+
+// In a super over the phase stays put between commits and reveals. The practice bot never commits.
+fn both_committed(game: &Game) -> bool {
+    game.p1_commitment.is_some() && (game.p2_commitment.is_some() || game.is_practice)
+}
+
+// Moves a round whose commitments are all in to its reveals. A super over reveals in the same phase.
+fn begin_reveals(env: &Env, game: &mut Game) -> Result<(), Error> {
+    game.phase = match game.phase {
+        Phase::TossCommit => Phase::TossReveal,
+        Phase::BallCommit => Phase::BallReveal,
+        Phase::SuperOver => Phase::SuperOver,
+        _ => return Err(Error::WrongPhase),
+    };
+    game.phase_started_ledger = env.ledger().sequence();
+    Ok(())
+}
+
+// The players' `commit_merkle` roots with leaves left for this innings stand as their commitments for a fresh
+// ball, moving it straight to reveals once both sides are in. Roots from an earlier innings, or spent ones, go.
+fn sync_merkle_commitments(env: &Env, session_id: u32, game: &mut Game) -> Result<(), Error> {
+    for is_player1 in [true, false] {
+        let player = if is_player1 { game.player1.clone() } else { game.player2.clone() };
+        let key = DataKey::MerkleCommitment(session_id, player);
+        let Some(merkle) = env.storage().temporary().get::<_, MerkleCommitment>(&key) else { continue };
+        if game.phase != Phase::BallCommit || merkle.innings != game.innings || merkle.next_index >= merkle.leaf_count {
+            env.storage().temporary().remove(&key);
+        } else if is_player1 {
+            game.p1_commitment = Some(merkle.root);
+        } else {
+            game.p2_commitment = Some(merkle.root);
+        }
+    }
+    if game.phase == Phase::BallCommit && both_committed(game) { begin_reveals(env, game)?; }
+    Ok(())
+}
+
+// The practice bot's number for the round being revealed.
+fn bot_number(env: &Env, game: &Game) -> u32 {
+    env.prng().gen_range::<u64>(game.min_number as u64..=game.max_number as u64) as u32
+}
+
+fn display_name(env: &Env, player: &Address) -> Option<Symbol> {
+    env.storage().persistent().get(&DataKey::Name(player.clone()))
+}
+
+pub fn pending_action(game: &Game, player: &Address) -> PendingAction {
+    let is_p1 = *player == game.player1;
+    // The bot moves inside its opponent's calls.
+    if game.is_practice && !is_p1 && game.phase != Phase::Finished { return PendingAction::Wait; }
+    let committed = if is_p1 { game.p1_commitment.is_some() } else { game.p2_commitment.is_some() };
+    let revealed = if is_p1 { game.p1_number.is_some() } else { game.p2_number.is_some() };
+    match game.phase {
+        Phase::TossCommit | Phase::BallCommit if !committed => PendingAction::Commit,
+        Phase::TossReveal | Phase::BallReveal if !revealed => PendingAction::Reveal,
+        Phase::SuperOver if !committed => PendingAction::Commit,
+        Phase::SuperOver if both_committed(game) && !revealed => PendingAction::Reveal,
+        Phase::BatBowlChoice if game.toss_winner.as_ref() == Some(player) => PendingAction::ChooseRole,
+        Phase::Finished => PendingAction::None,
+        _ => PendingAction::Wait,
+    }
+}
+
+// The ledger from which the waiting player may `claim_timeout`, or None unless exactly one player owes a move.
+fn timeout_deadline(env: &Env, game: &Game) -> Option<u32> {
+    let p1_waits = pending_action(game, &game.player1) == PendingAction::Wait;
+    let p2_waits = pending_action(game, &game.player2) == PendingAction::Wait;
+    if game.phase == Phase::Finished || p1_waits == p2_waits { return None; }
+    Some(game.last_action_ledger.saturating_add(HandCricketContract::get_timeout_ledgers(env.clone())))
+}
+
+// Stable names for explorers and low-code clients. Every enum variant maps here and nowhere else;
+// the matches are exhaustive so a new variant can't ship without a name.
+pub fn phase_symbol(env: &Env, phase: &Phase) -> Symbol {
+    Symbol::new(env, match phase {
+        Phase::TossCommit => "toss_commit",
+        Phase::TossReveal => "toss_reveal",
+        Phase::BatBowlChoice => "role_choice",
+        Phase::BallCommit => "ball_commit",
+        Phase::BallReveal => "ball_reveal",
+        Phase::SuperOver => "super_over",
+        Phase::Finished => "finished",
+    })
+}
+
+pub fn result_symbol(env: &Env, game: &Game) -> Symbol {
+    Symbol::new(env, match &game.winner {
+        None if game.voided => "voided",
+        None if game.phase == Phase::Finished => "tie",
+        None => "in_play",
+        Some(w) if *w == game.player1 => "p1_won",
+        Some(_) => "p2_won",
+    })
+}
+
+pub fn turn(env: &Env, game: &Game) -> Turn {
+    let owing = |action: PendingAction| {
+        let mut players = Vec::new(env);
+        for player in [&game.player1, &game.player2] {
+            if pending_action(game, player) == action { players.push_back(player.clone()); }
+        }
+        players
+    };
+    match (&game.phase, &game.toss_winner) {
+        (Phase::TossCommit | Phase::BallCommit, _) => Turn::CommitFrom(owing(PendingAction::Commit)),
+        (Phase::TossReveal | Phase::BallReveal, _) => Turn::RevealFrom(owing(PendingAction::Reveal)),
+        (Phase::SuperOver, _) if both_committed(game) => Turn::RevealFrom(owing(PendingAction::Reveal)),
+        (Phase::SuperOver, _) => Turn::CommitFrom(owing(PendingAction::Commit)),
+        (Phase::BatBowlChoice, Some(toss_winner)) => Turn::RoleChoiceFrom(toss_winner.clone()),
+        // A role choice without a toss winner breaks `TossWinnerSetAfterToss`; nobody can move.
+        (Phase::BatBowlChoice, None) | (Phase::Finished, _) => Turn::None,
+    }
+}
+
+pub fn expected_action(game: &Game, player: &Address) -> ExpectedAction {
+    if game.phase == Phase::Finished { return ExpectedAction::GameFinished; }
+    let is_p1 = *player == game.player1;
+    if !is_p1 && *player != game.player2 { return ExpectedAction::NotParticipant; }
+    let committed = if is_p1 { game.p1_commitment.is_some() } else { game.p2_commitment.is_some() };
+    let revealed = if is_p1 { game.p1_number.is_some() } else { game.p2_number.is_some() };
+    match game.phase {
+        Phase::TossCommit | Phase::BallCommit if committed => ExpectedAction::AwaitOpponentCommit,
+        Phase::TossReveal | Phase::BallReveal if revealed => ExpectedAction::AwaitOpponentReveal,
+        Phase::SuperOver if both_committed(game) && revealed => ExpectedAction::AwaitOpponentReveal,
+        Phase::SuperOver if both_committed(game) => ExpectedAction::RevealBall,
+        Phase::SuperOver if committed => ExpectedAction::AwaitOpponentCommit,
+        Phase::SuperOver => ExpectedAction::CommitBall,
+        Phase::TossCommit => ExpectedAction::CommitToss,
+        Phase::TossReveal => ExpectedAction::RevealToss,
+        Phase::BatBowlChoice if game.toss_winner.as_ref() == Some(player) => ExpectedAction::ChooseRole,
+        Phase::BatBowlChoice => ExpectedAction::AwaitRoleChoice,
+        Phase::BallCommit => ExpectedAction::CommitBall,
+        Phase::BallReveal => ExpectedAction::RevealBall,
+        Phase::Finished => ExpectedAction::GameFinished,
+    }
+}
+
+// Who bats first when `chooser` won the toss and elected to bat (or not).
+fn batter_for(game: &Game, chooser: &Address, bat: bool) -> Address {
+    if bat == (*chooser == game.player1) { game.player1.clone() } else { game.player2.clone() }
+}
+
+// The error for a call the current phase doesn't accept, naming what it does accept.
+fn wrong_phase(game: &Game) -> Error {
+    match game.phase {
+        Phase::TossCommit | Phase::BallCommit => Error::WrongPhaseExpectedCommit,
+        Phase::TossReveal | Phase::BallReveal => Error::WrongPhaseExpectedReveal,
+        Phase::SuperOver if both_committed(game) => Error::WrongPhaseExpectedReveal,
+        Phase::SuperOver => Error::WrongPhaseExpectedCommit,
+        Phase::BatBowlChoice => Error::WrongPhaseExpectedRoleChoice,
+        Phase::Finished => Error::GameAlreadyEnded,
+    }
+}
+
+// Returns every invariant the stored game violates; empty means the state is legal.
+pub fn validate_invariants(env: &Env, game: &Game) -> Vec<Invariant> {
+    let mut failed = Vec::new(env);
+    let is_player = |a: &Address| *a == game.player1 || *a == game.player2;
+    let both_committed = both_committed(game);
+    let revealing = matches!(game.phase, Phase::TossReveal | Phase::BallReveal) || (game.phase == Phase::SuperOver && both_committed);
+    let balls = matches!(game.phase, Phase::BallCommit | Phase::BallReveal | Phase::SuperOver);
+    let none_committed = game.p1_commitment.is_none() && game.p2_commitment.is_none();
+
+    if game.winner.is_some() && game.phase != Phase::Finished { failed.push_back(Invariant::WinnerOnlyWhenFinished); }
+    if game.winner.as_ref().is_some_and(|w| !is_player(w)) { failed.push_back(Invariant::WinnerIsPlayer); }
+    if (game.p1_number.is_some() || game.p2_number.is_some()) && !revealing && game.phase != Phase::Finished {
+        failed.push_back(Invariant::NumbersOnlyWhenRevealing);
+    }
+    if (game.p1_number.is_some() && game.p1_commitment.is_none()) || (game.p2_number.is_some() && game.p2_commitment.is_none() && !game.is_practice) {
+        failed.push_back(Invariant::NumberWithoutCommitment);
+    }
+    let commitments_ok = match game.phase {
+        Phase::TossCommit | Phase::BallCommit => !both_committed,
+        Phase::TossReveal | Phase::BallReveal => both_committed,
+        Phase::BatBowlChoice => none_committed,
+        Phase::SuperOver | Phase::Finished => true,
+    };
+    if !commitments_ok { failed.push_back(Invariant::CommitmentsMatchPhase); }
+    if balls && !game.batter.as_ref().is_some_and(is_player) { failed.push_back(Invariant::BatterSetDuringBalls); }
+    if (balls || game.phase == Phase::BatBowlChoice) && !game.toss_winner.as_ref().is_some_and(is_player) {
+        failed.push_back(Invariant::TossWinnerSetAfterToss);
+    }
+    let innings_ok = match game.innings {
+        1 => game.target == 0,
+        2 => game.target >= 1,
+        3 | 4 => game.target >= 1 && game.super_over >= 1,
+        _ => false,
+    };
+    if !innings_ok { failed.push_back(Invariant::InningsAndTarget); }
+    if ball_limit(game) > 0 && game.balls_bowled > ball_limit(game) { failed.push_back(Invariant::BallsWithinLimit); }
+    failed
+}
+
+#[contract]
+pub struct HandCricketContract;
+
+#[contractimpl]
+impl HandCricketContract {
+
+    // `config` goes through the same checks as the individual setters; `None` keeps every default.
+    // Without a hub only friendly, practice and token-escrowed games can be played.
+    pub fn __constructor(env: Env, admin: Address, game_hub: Option<Address>, config: Option<InitConfig>) {
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        if let Some(game_hub) = game_hub { env.storage().instance().set(&DataKey::GameHubAddress, &game_hub); }
+        env.storage().instance().set(&DataKey::CodeVersion, &CONTRACT_VERSION);
+        if let Err(e) = Self::apply_init_config(&env, config.unwrap_or_default()) { panic_with_error!(&env, e); }
+    }
+
+    fn apply_init_config(env: &Env, config: InitConfig) -> Result<(), Error> {
+        if let Some(operator) = config.operator { Self::apply_admin_op(env, AdminOp::SetOperator(operator))?; }
+        if let Some(ledgers) = config.rollback_window_ledgers { Self::apply_admin_op(env, AdminOp::SetRollbackWindow(ledgers))?; }
+        if let Some(forward) = config.forward_starts { Self::apply_admin_op(env, AdminOp::SetForwardStarts(forward))?; }
+        if let Some(accept_any) = config.accept_any_vk { Self::apply_admin_op(env, AdminOp::SetAcceptAnyVk(accept_any))?; }
+        if let Some(ledgers) = config.reservation_ledgers { Self::apply_admin_op(env, AdminOp::SetReservationLedgers(ledgers))?; }
+        if let Some(ledgers) = config.dispute_window_ledgers { Self::apply_admin_op(env, AdminOp::SetDisputeWindow(ledgers))?; }
+        if let Some(ledgers) = config.timeout_ledgers { Self::apply_admin_op(env, AdminOp::SetTimeoutLedgers(ledgers))?; }
+        if let Some(verifier) = config.verifier { Self::apply_admin_op(env, AdminOp::SetVerifier(Some(verifier)))?; }
+        if let Some(balls) = config.super_over_balls { Self::apply_admin_op(env, AdminOp::SetSuperOverBalls(balls))?; }
+        if let Some(ledgers) = config.open_game_ledgers { Self::apply_admin_op(env, AdminOp::SetOpenGameLedgers(ledgers))?; }
+        if let Some(ledgers) = config.game_ttl_ledgers { Self::apply_admin_op(env, AdminOp::SetGameTtl(ledgers))?; }
+        if config.min_stake_points.is_some() || config.max_stake_points.is_some() {
+            let defaults = Self::get_stake_limits(env.clone());
+            let limits = StakeLimits {
+                min_points: config.min_stake_points.unwrap_or(defaults.min_points),
+                max_points: config.max_stake_points.unwrap_or(defaults.max_points),
+            };
+            Self::apply_admin_op(env, AdminOp::SetStakeLimits(limits))?;
+        }
+        if let Some(equal) = config.require_equal_stakes { Self::apply_admin_op(env, AdminOp::SetRequireEqualStakes(equal))?; }
+        if let Some(bps) = config.fee_bps { Self::apply_admin_op(env, AdminOp::SetFeeBps(bps))?; }
+        if let Some(legacy) = config.legacy_hub { Self::apply_admin_op(env, AdminOp::SetLegacyHub(legacy))?; }
+        if let Some(supported) = config.hub_supports_cancel { Self::apply_admin_op(env, AdminOp::SetHubSupportsCancel(supported))?; }
+        if let Some(allowed) = config.direct_start_allowed { Self::apply_admin_op(env, AdminOp::SetDirectStartAllowed(allowed))?; }
+        let disabled = config.disabled_features.unwrap_or(0);
+        for bit in (0..u32::BITS).map(|i| 1 << i).filter(|bit| disabled & bit != 0) {
+            Self::apply_admin_op(env, AdminOp::DisableFeature(bit))?;
+        }
+        Ok(())
+    }
+
+    pub fn get_config(env: Env) -> Config {
+        Config {
+            admin: Self::get_admin(env.clone()),
+            hub: Self::get_hub(env.clone()),
+            operator: Self::get_operator(env.clone()),
+            rollback_window_ledgers: Self::get_rollback_window(env.clone()),
+            forward_starts: Self::get_forward_starts(env.clone()),
+            accept_any_vk: env.storage().instance().get(&DataKey::AcceptAnyVk).unwrap_or(false),
+            enabled_features: enabled_features(&env),
+            reservation_ledgers: Self::get_reservation_ledgers(env.clone()),
+            dispute_window_ledgers: Self::get_dispute_window(env.clone()),
+            timeout_ledgers: Self::get_timeout_ledgers(env.clone()),
+            verifier: Self::get_verifier(env.clone()),
+            super_over_balls: Self::get_super_over_balls(env.clone()),
+            open_game_ledgers: Self::get_open_game_ledgers(env.clone()),
+            game_ttl_ledgers: game_ttl(&env),
+            stake_limits: Self::get_stake_limits(env.clone()),
+            require_equal_stakes: Self::get_require_equal_stakes(env.clone()),
+            fee_bps: Self::get_fee_bps(env.clone()),
+            legacy_hub: Self::get_legacy_hub(env.clone()),
+            hub_supports_cancel: Self::get_hub_supports_cancel(env.clone()),
+            direct_start_allowed: Self::get_direct_start_allowed(env.clone()),
+        }
+    }
+
+    // Returns the opened game as `get_game_public` shows it, so clients needn't read it back.
+    pub fn start_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128) -> Result<GamePublicView, Error> {
+        Self::open_game(env.clone(), session_id, player1, player2, player1_points, player2_points, None, None, None, None)?;
+        Self::get_game_public(env, session_id)
+    }
+
+    // `start_game` on `hub` instead of the configured one, for deployments that serve several hubs. `hub` must
+    // be the configured hub or on `get_allowed_hubs`; both players sign over it as well as their stake.
+    pub fn start_game_on_hub(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, hub: Address) -> Result<(), Error> {
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, None, None, Some(hub))
+    }
+
+    // `start_game` called by the hub for a session it has already opened, so it isn't told again. Only the
+    // configured hub may call it, and the players still sign over the same arguments as for `start_game`.
+    // No fee is withheld: the hub set the stakes.
+    pub fn start_from_hub(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128) -> Result<(), Error> {
+        Self::check_can_open(&env, session_id, &player1, &player2, None)?;
+        Self::check_stakes(&env, player1_points, player2_points)?;
+        let hub = Self::get_hub(env.clone()).ok_or(Error::NoHub)?;
+        hub.require_auth();
+        player1.require_auth_for_args(vec![&env, session_id.into_val(&env), player1_points.into_val(&env)]);
+        player2.require_auth_for_args(vec![&env, session_id.into_val(&env), player2_points.into_val(&env)]);
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player1, &player2);
+        let vk_version = Self::select_vk_version(&env)?;
+        let game = Game { hub: Some(hub), ..new_game(&env, player1, player2, player1_points, player2_points, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    // `start_game` with an organizer tag for indexers. A named organizer must authorize it too.
+    pub fn start_labeled_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, label: GameLabel) -> Result<(), Error> {
+        if symbol_len(&env, &label.label) > MAX_LABEL_LEN { return Err(Error::LabelTooLong); }
+        if let Some(organizer) = &label.organizer { organizer.require_auth(); }
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, Some(label), None, None)
+    }
+
+    // `start_configured_game` with each innings ending after `max_balls_per_innings` balls even without a wicket;
+    // 0 is unlimited. A chase still level when its balls run out is tied, as when the batter is out one run short:
+    // it goes to a super over, or is drawn when `get_super_over_balls` is 0.
+    pub fn start_limited_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, max_balls_per_innings: u32) -> Result<(), Error> {
+        let config = GameConfig { max_balls_per_innings, ..GameConfig::default() };
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, None, Some(config), None)
+    }
+
+    // `start_game` with the options in `config`: hands from `min_number` to `max_number` instead of 1 to 6 (e.g. 0
+    // to 6 with 0 as a thumbs up, or 1 to 10), a ball cap, a handicap and signing keys. Both players sign over it.
+    pub fn start_configured_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, config: GameConfig) -> Result<(), Error> {
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, None, Some(config), None)
+    }
+
+    // `start_configured_game` with the first innings' target moved by `handicap` runs in favour of player1 (positive)
+    // or player2 (negative), whichever of them bats first; it never drops below 1.
+    pub fn start_handicapped_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, handicap: i32) -> Result<(), Error> {
+        let config = GameConfig { handicap, ..GameConfig::default() };
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, None, Some(config), None)
+    }
+
+    // `start_game` followed by player1's toss commitment, atomically; player1's auth also covers the commitment.
+    pub fn start_and_commit(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, p1_toss_commitment: BytesN<32>) -> Result<(), Error> {
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, Some(p1_toss_commitment), None, None, None)
+    }
+
+    // `start_configured_game` with the ed25519 keys each player will sign `settle_signed_balls` moves with. Both
+    // players' auth covers the keys, so a relayer can't swap one in.
+    #[allow(clippy::too_many_arguments)]
+    pub fn start_game_with_keys(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, p1_key: BytesN<32>, p2_key: BytesN<32>) -> Result<(), Error> {
+        let config = GameConfig { p1_key: Some(p1_key), p2_key: Some(p2_key), ..GameConfig::default() };
+        Self::open_game(env, session_id, player1, player2, player1_points, player2_points, None, None, Some(config), None)
+    }
+
+    // `start_game` on the next id from an internal counter, returned to the caller. The id is only known once
+    // the call lands, so each player signs over their opponent, the configured hub and their own stake instead.
+    pub fn start_game_auto(env: Env, player1: Address, player2: Address, player1_points: i128, player2_points: i128) -> Result<u32, Error> {
+        let session_id = Self::get_next_session(env.clone()).ok_or(Error::SessionReserved)?;
+        env.storage().instance().set(&DataKey::NextSession, &(session_id as u64 + 1));
+        Self::check_can_open(&env, session_id, &player1, &player2, None)?;
+        Self::check_stakes(&env, player1_points, player2_points)?;
+        let hub = Self::get_hub(env.clone());
+        player1.require_auth_for_args(vec![&env, player2.into_val(&env), hub.into_val(&env), player1_points.into_val(&env)]);
+        player2.require_auth_for_args(vec![&env, player1.into_val(&env), hub.into_val(&env), player2_points.into_val(&env)]);
+
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player1, &player2);
+        let vk_version = Self::select_vk_version(&env)?;
+        let hub = Self::hub_start_game(&env, None, session_id, &player1, &player2, player1_points, player2_points)?;
+        let game = Game { hub: Some(hub), ..new_game(&env, player1, player2, player1_points, player2_points, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)?;
+        Ok(session_id)
+    }
+
+    // A game against this contract for learning the flow: no hub session and no stakes. The player commits
+    // and reveals as usual; the bot draws its number with the PRNG when the player reveals, and picks its role
+    // the same way when it wins the toss. Practice results stay out of stats and the leaderboard.
+    pub fn start_practice_game(env: Env, session_id: u32, player: Address) -> Result<(), Error> {
+        require_feature(&env, features::PRACTICE)?;
+        let bot = env.current_contract_address();
+        Self::check_can_open(&env, session_id, &player, &bot, None)?;
+        player.require_auth_for_args(vec![&env, session_id.into_val(&env)]);
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player, &bot);
+        let vk_version = Self::select_vk_version(&env)?;
+        let game = Game { is_practice: true, ..new_game(&env, player, bot, 0, 0, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    // A game played for fun: no stakes and no hub session, so it works on deployments without a hub. Toss,
+    // innings, results, stats and events are as for any other game. Both players sign over the session id.
+    pub fn start_friendly_game(env: Env, session_id: u32, player1: Address, player2: Address) -> Result<(), Error> {
+        Self::check_can_open(&env, session_id, &player1, &player2, None)?;
+        player1.require_auth_for_args(vec![&env, session_id.into_val(&env)]);
+        player2.require_auth_for_args(vec![&env, session_id.into_val(&env)]);
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player1, &player2);
+        let vk_version = Self::select_vk_version(&env)?;
+        let game = Game { friendly: true, ..new_game(&env, player1, player2, 0, 0, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    // A fresh game on `new_session_id` between the players of the finished `old_session_id`, on the same stakes
+    // and format with odd and even swapped. Both players sign over both ids.
+    pub fn rematch(env: Env, old_session_id: u32, new_session_id: u32) -> Result<(), Error> {
+        let (old, _) = read_game(&env, old_session_id)?;
+        if old.phase != Phase::Finished { return Err(Error::GameNotFinished); }
+        if old.is_practice { return Err(Error::PracticeGame); }
+        if old.escrow_token.is_some() { return Err(Error::EscrowGame); }
+        Self::check_can_open(&env, new_session_id, &old.player1, &old.player2, None)?;
+        // Friendly games stake nothing, so the limits don't apply.
+        if !old.friendly { Self::check_stakes(&env, old.player1_points, old.player2_points)?; }
+        let args = vec![&env, old_session_id.into_val(&env), new_session_id.into_val(&env)];
+        old.player1.require_auth_for_args(args.clone());
+        old.player2.require_auth_for_args(args);
+
+        let vk_version = Self::select_vk_version(&env)?;
+        // On the old game's hub, if it is still allowed.
+        let hub = if old.friendly {
+            None
+        } else {
+            Some(Self::hub_start_game(&env, old.hub.clone(), new_session_id, &old.player1, &old.player2, old.player1_points, old.player2_points)?)
+        };
+
+        let game = Game {
+            hub,
+            max_balls_per_innings: old.max_balls_per_innings,
+            min_number: old.min_number,
+            max_number: old.max_number,
+            handicap: old.handicap,
+            friendly: old.friendly,
+            ..new_game(&env, old.player1.clone(), old.player2.clone(), old.player1_points, old.player2_points, !old.player1_is_odd, vk_version)
+        };
+        Self::begin_game(&env, new_session_id, &game)
+    }
+
+    // The first half of `start_game` for players who can't sign together: only the challenger signs here,
+    // and `opponent` starts the game by signing `accept_challenge` before the challenge expires.
+    pub fn create_challenge(env: Env, session_id: u32, challenger: Address, opponent: Address, challenger_points: i128, opponent_points: i128) -> Result<Challenge, Error> {
+        Self::check_can_open(&env, session_id, &challenger, &opponent, None)?;
+        Self::check_stakes(&env, challenger_points, opponent_points)?;
+        challenger.require_auth();
+        let expires_ledger = env.ledger().sequence().saturating_add(CHALLENGE_LEDGERS);
+        let challenge = Challenge { challenger, opponent, challenger_points, opponent_points, expires_ledger };
+        let key = DataKey::Challenge(session_id);
+        // Kept past its expiry so a late accept is told why it failed.
+        env.storage().temporary().set(&key, &challenge);
+        env.storage().temporary().extend_ttl(&key, 2 * CHALLENGE_LEDGERS, 2 * CHALLENGE_LEDGERS);
+        Ok(challenge)
+    }
+
+    // Starts the challenged game as `start_game` would, with the challenger as player1. The opponent signs
+    // over their own stake, so a challenge replaced in the meantime can't be accepted on other terms.
+    pub fn accept_challenge(env: Env, session_id: u32, opponent: Address) -> Result<(), Error> {
+        let key = DataKey::Challenge(session_id);
+        let challenge: Challenge = env.storage().temporary().get(&key).ok_or(Error::ChallengeNotFound)?;
+        if challenge.expires_ledger <= env.ledger().sequence() { return Err(Error::ChallengeExpired); }
+        if opponent != challenge.opponent { return Err(Error::NotPlayer); }
+        opponent.require_auth_for_args(vec![&env, session_id.into_val(&env), challenge.opponent_points.into_val(&env)]);
+        env.storage().temporary().remove(&key);
+        let Challenge { challenger, challenger_points, opponent_points, .. } = challenge;
+        Self::check_can_open(&env, session_id, &challenger, &opponent, None)?;
+        Self::check_stakes(&env, challenger_points, opponent_points)?;
+
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &challenger, &opponent);
+        let vk_version = Self::select_vk_version(&env)?;
+        let hub = Self::hub_start_game(&env, None, session_id, &challenger, &opponent, challenger_points, opponent_points)?;
+        let game = Game { hub: Some(hub), ..new_game(&env, challenger, opponent, challenger_points, opponent_points, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    pub fn cancel_challenge(env: Env, session_id: u32) -> Result<(), Error> {
+        let key = DataKey::Challenge(session_id);
+        let challenge: Challenge = env.storage().temporary().get(&key).ok_or(Error::ChallengeNotFound)?;
+        challenge.challenger.require_auth();
+        env.storage().temporary().remove(&key);
+        Ok(())
+    }
+
+    // None once accepted, cancelled or expired.
+    pub fn get_challenge(env: Env, session_id: u32) -> Option<Challenge> {
+        let challenge: Challenge = env.storage().temporary().get(&DataKey::Challenge(session_id))?;
+        if challenge.expires_ledger > env.ledger().sequence() { Some(challenge) } else { None }
+    }
+
+    // Posts a game to the lobby; the first address to `join_open_game` it with `required_opponent_points`
+    // becomes player2. Only the host signs here.
+    pub fn create_open_game(env: Env, session_id: u32, host: Address, host_points: i128, required_opponent_points: i128) -> Result<OpenGame, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        if env.storage().instance().has(&DataKey::Successor) { return Err(Error::Deprecated); }
+        if Self::session_held(&env, session_id) { return Err(Error::SessionExists); }
+        if session_id as u64 >= next_auto_session(&env) { return Err(Error::SessionReserved); }
+        Self::check_stakes(&env, host_points, required_opponent_points)?;
+        if is_blocked(&env, &host) { return Err(Error::Blocked); }
+        host.require_auth();
+        let mut index = Self::list_open_games(env.clone());
+        if index.len() >= MAX_OPEN_GAMES { return Err(Error::LobbyFull); }
+        index.push_back(session_id);
+        env.storage().instance().set(&DataKey::OpenGames, &index);
+
+        let expires_ledger = env.ledger().sequence().saturating_add(Self::get_open_game_ledgers(env.clone()));
+        let open = OpenGame { host, host_points, opponent_points: required_opponent_points, expires_ledger };
+        let key = DataKey::OpenGame(session_id);
+        env.storage().temporary().set(&key, &open);
+        extend_with_game_ttl(&env, &key);
+        Ok(open)
+    }
+
+    // Takes the open game as player2 and starts it as `start_game` would. The joiner signs over the stake.
+    // Once someone has joined, later joiners get AlreadyJoined.
+    pub fn join_open_game(env: Env, session_id: u32, joiner: Address) -> Result<(), Error> {
+        let key = DataKey::OpenGame(session_id);
+        let Some(open) = open_game(&env, session_id) else {
+            let joined = env.storage().temporary().has(&DataKey::Game(session_id)) && !env.storage().temporary().has(&key);
+            return Err(if joined { Error::AlreadyJoined } else { Error::GameNotFound });
+        };
+        if joiner == open.host { return Err(Error::SelfPlay); }
+        joiner.require_auth_for_args(vec![&env, session_id.into_val(&env), open.opponent_points.into_val(&env)]);
+        env.storage().temporary().remove(&key);
+        let mut index = open_game_index(&env);
+        if let Some(i) = index.first_index_of(session_id) { index.remove(i); }
+        env.storage().instance().set(&DataKey::OpenGames, &index);
+        let OpenGame { host, host_points, opponent_points, .. } = open;
+        Self::check_can_open(&env, session_id, &host, &joiner, None)?;
+        Self::check_stakes(&env, host_points, opponent_points)?;
+
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &host, &joiner);
+        let vk_version = Self::select_vk_version(&env)?;
+        let hub = Self::hub_start_game(&env, None, session_id, &host, &joiner, host_points, opponent_points)?;
+        let game = Game { hub: Some(hub), ..new_game(&env, host, joiner, host_points, opponent_points, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    // Session ids of the open games waiting for a joiner, oldest first.
+    pub fn list_open_games(env: Env) -> Vec<u32> {
+        let mut live = Vec::new(&env);
+        for session_id in open_game_index(&env).iter().filter(|id| open_game(&env, *id).is_some()) { live.push_back(session_id); }
+        live
+    }
+
+    // Drops expired open games from the lobby index and storage, returning how many went. Anyone may call it.
+    pub fn clean_open_games(env: Env) -> u32 {
+        let index = open_game_index(&env);
+        let live = Self::list_open_games(env.clone());
+        for session_id in index.iter().filter(|id| !live.contains(id)) {
+            env.storage().temporary().remove(&DataKey::OpenGame(session_id));
+        }
+        env.storage().instance().set(&DataKey::OpenGames, &live);
+        index.len() - live.len()
+    }
+
+    pub fn get_open_game(env: Env, session_id: u32) -> Option<OpenGame> { open_game(&env, session_id) }
+
+    #[allow(clippy::too_many_arguments)]
+    fn open_game(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, p1_commitment: Option<BytesN<32>>, label: Option<GameLabel>, config: Option<GameConfig>, hub: Option<Address>) -> Result<(), Error> {
+        Self::check_can_open(&env, session_id, &player1, &player2, label.as_ref())?;
+        Self::check_stakes(&env, player1_points, player2_points)?;
+        if let Some(config) = &config {
+            if config.min_number >= config.max_number || config.max_number > MAX_NUMBER_CAP { return Err(Error::InvalidNumberRange); }
+        }
+        let mut p1_args = vec![&env, session_id.into_val(&env), player1_points.into_val(&env)];
+        let mut p2_args = vec![&env, session_id.into_val(&env), player2_points.into_val(&env)];
+        if let Some(commitment) = &p1_commitment { p1_args.push_back(commitment.into_val(&env)); }
+        if let Some(config) = &config {
+            p1_args.push_back(config.into_val(&env));
+            p2_args.push_back(config.into_val(&env));
+        }
+        if let Some(hub) = &hub {
+            p1_args.push_back(hub.into_val(&env));
+            p2_args.push_back(hub.into_val(&env));
+        }
+        player1.require_auth_for_args(p1_args);
+        player2.require_auth_for_args(p2_args);
+
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player1, &player2);
+        let vk_version = Self::select_vk_version(&env)?;
+
+        let hub = Self::hub_start_game(&env, hub, session_id, &player1, &player2, player1_points, player2_points)?;
+
+        let config = config.unwrap_or_default();
+        let game = Game {
+            hub: Some(hub),
+            p1_commitment,
+            organizer: label.as_ref().and_then(|l| l.organizer.clone()),
+            label: label.map(|l| l.label),
+            max_balls_per_innings: config.max_balls_per_innings,
+            min_number: config.min_number,
+            max_number: config.max_number,
+            handicap: config.handicap,
+            p1_key: config.p1_key,
+            p2_key: config.p2_key,
+            ..new_game(&env, player1, player2, player1_points, player2_points, player1_is_odd, vk_version)
+        };
+        Self::begin_game(&env, session_id, &game)
+    }
+
+    // Checks shared by every way of starting a session the hub will see.
+    // Stakes outside `get_stake_limits` would only be refused by the hub once the game is under way.
+    fn check_stakes(env: &Env, player1_points: i128, player2_points: i128) -> Result<(), Error> {
+        let limits = Self::get_stake_limits(env.clone());
+        for points in [player1_points, player2_points] {
+            if points < limits.min_points { return Err(Error::StakeTooLow); }
+            if points > limits.max_points { return Err(Error::StakeTooHigh); }
+        }
+        if player1_points != player2_points && Self::get_require_equal_stakes(env.clone()) { return Err(Error::UnequalStakes); }
+        Ok(())
+    }
+
+    fn check_can_open(env: &Env, session_id: u32, player1: &Address, player2: &Address, label: Option<&GameLabel>) -> Result<(), Error> {
+        assert_not_retired(env);
+        ensure_migrated(env)?;
+        if env.storage().instance().has(&DataKey::Successor) { return Err(Error::Deprecated); }
+        if player1 == player2 { return Err(Error::SelfPlay); }
+        if Self::session_held(env, session_id) { return Err(Error::SessionExists); }
+        if session_id as u64 >= next_auto_session(env) { return Err(Error::SessionReserved); }
+        if is_blocked(env, player1) || is_blocked(env, player2) { return Err(Error::Blocked); }
+        if let Some(reservation) = active_reservations(env).iter().find(|r| (r.from..=r.to).contains(&session_id)) {
+            // Only starts the organizer has authorized, through `start_labeled_game`, may use a reserved id.
+            if label.and_then(|l| l.organizer.as_ref()) != Some(&reservation.organizer) { return Err(Error::SessionReserved); }
+        }
+        if let Some(hold) = creator_reservation(env, session_id) {
+            if &hold.creator != player1 && &hold.creator != player2 { return Err(Error::SessionReserved); }
+            env.storage().temporary().remove(&DataKey::CreatorReservation(session_id));
+        }
+        Ok(())
+    }
+
+    // A live game, or a challenge or open game still waiting for its opponent.
+    fn session_held(env: &Env, session_id: u32) -> bool {
+        !Self::session_available(env.clone(), session_id)
+            || Self::get_challenge(env.clone(), session_id).is_some()
+            || open_game(env, session_id).is_some()
+    }
+
+    fn begin_game(env: &Env, session_id: u32, game: &Game) -> Result<(), Error> {
+        write_game(env, session_id, game);
+        index_game(env, session_id, &game.player1)?;
+        // The practice bot would run out of slots.
+        if !game.is_practice { index_game(env, session_id, &game.player2)?; }
+        let active = Self::active_session_count(env.clone());
+        env.storage().instance().set(&DataKey::ActiveSessions, &(active + 1));
+        GameStarted { session_id, player1: game.player1.clone(), player2: game.player2.clone(), phase: phase_symbol(env, &game.phase) }.publish(env);
+        publish_watched(env, session_id, game, symbol_short!("started"));
+        Ok(())
+    }
+
+    // Same as `start_game`, but once deprecated the start may be forwarded to the successor when the caller
+    // allows it and the admin has enabled forwarding. Returns the successor when it was, None when played here.
+    // Forwarded starts need the players' auth to cover the successor's `start_game` as a sub-invocation.
+    pub fn start_game_redirectable(env: Env, session_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, allow_redirect: bool) -> Result<Option<Address>, Error> {
+        assert_not_retired(&env);
+        let Some(successor) = Self::get_redirect(env.clone()) else {
+            Self::start_game(env, session_id, player1, player2, player1_points, player2_points)?;
+            return Ok(None);
+        };
+        if !allow_redirect || !Self::get_forward_starts(env.clone()) { return Err(Error::Deprecated); }
+        player1.require_auth_for_args(vec![&env, session_id.into_val(&env), player1_points.into_val(&env)]);
+        player2.require_auth_for_args(vec![&env, session_id.into_val(&env), player2_points.into_val(&env)]);
+
+        HandCricketContractClient::new(&env, &successor).start_game(&session_id, &player1, &player2, &player1_points, &player2_points);
+        StartRedirected { session_id, successor: successor.clone() }.publish(&env);
+        Ok(Some(successor))
+    }
+
+    pub fn commit_number(env: Env, session_id: u32, player: Address, commitment: BytesN<32>) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        Self::apply_commit(&env, session_id, &player, commitment)
+    }
+
+    fn apply_commit(env: &Env, session_id: u32, player: &Address, commitment: BytesN<32>) -> Result<GamePublicView, Error> {
+        let (mut game, _) = read_game(env, session_id)?;
+        // A retry of a commitment that already landed is a no-op, for as long as the commitment is stored.
+        let stored = if *player == game.player1 { &game.p1_commitment } else if *player == game.player2 { &game.p2_commitment } else { &None };
+        if stored.as_ref() == Some(&commitment) { return Ok(public_view(env, session_id, &game)); }
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        match game.phase { Phase::TossCommit | Phase::BallCommit => {} Phase::SuperOver if !both_committed(&game) => {} _ => return Err(wrong_phase(&game)), }
+
+        // Copying the opponent's digest would let the second committer mirror their number.
+        if *player == game.player1 {
+            if game.p1_commitment.is_some() { return Err(Error::AlreadyCommitted); }
+            if game.p2_commitment.as_ref() == Some(&commitment) { return Err(Error::DuplicateCommitment); }
+            game.p1_commitment = Some(commitment);
+        } else if *player == game.player2 && !game.is_practice {
+            if game.p2_commitment.is_some() { return Err(Error::AlreadyCommitted); }
+            if game.p1_commitment.as_ref() == Some(&commitment) { return Err(Error::DuplicateCommitment); }
+            game.p2_commitment = Some(commitment);
+        } else { return Err(Error::NotPlayer); }
+
+        if both_committed(&game) { begin_reveals(env, &mut game)?; }
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(env, session_id, &game);
+        Ok(public_view(env, session_id, &game))
+    }
+
+    // Swaps the caller's commitment for this round, for a player whose client committed the wrong hash. Only
+    // while the opponent has not committed: once both are in, the round has moved on to its reveals.
+    pub fn replace_commitment(env: Env, session_id: u32, player: Address, new_commitment: BytesN<32>) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        let is_player1 = player == game.player1;
+        if !is_player1 && (player != game.player2 || game.is_practice) { return Err(Error::NotPlayer); }
+        if both_committed(&game) || !matches!(game.phase, Phase::TossCommit | Phase::BallCommit | Phase::SuperOver) {
+            return Err(Error::WrongPhase);
+        }
+        // The opponent hasn't committed, so there is no digest yet to mirror.
+        let stored = if is_player1 { &mut game.p1_commitment } else { &mut game.p2_commitment };
+        if stored.is_none() { return Err(Error::CommitMissing); }
+        *stored = Some(new_commitment.clone());
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(&env, session_id, &game);
+        CommitmentReplaced { session_id, player, commitment: new_commitment }.publish(&env);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Both commitments for the round at once, for clients that relay both players' signed entries. Each player
+    // (or their delegate) signs over the session and their own commitment only, and the round moves straight to
+    // its reveals. Either commitment failing a `commit_number` check stores neither.
+    pub fn commit_both(env: Env, session_id: u32, p1_commitment: BytesN<32>, p2_commitment: BytesN<32>) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        let (mut game, _) = read_game(&env, session_id)?;
+        for (player, commitment) in [(&game.player1, &p1_commitment), (&game.player2, &p2_commitment)] {
+            move_signer(&env, session_id, player).require_auth_for_args(vec![&env, session_id.into_val(&env), commitment.into_val(&env)]);
+        }
+        // A retry of a pair that already landed is a no-op, as in `commit_number`.
+        if game.p1_commitment.as_ref() == Some(&p1_commitment) && game.p2_commitment.as_ref() == Some(&p2_commitment) {
+            return Ok(public_view(&env, session_id, &game));
+        }
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if game.is_practice { return Err(Error::NotPlayer); }
+        match game.phase { Phase::TossCommit | Phase::BallCommit | Phase::SuperOver => {} _ => return Err(wrong_phase(&game)), }
+        if game.p1_commitment.is_some() || game.p2_commitment.is_some() { return Err(Error::AlreadyCommitted); }
+        if p1_commitment == p2_commitment { return Err(Error::DuplicateCommitment); }
+
+        game.p1_commitment = Some(p1_commitment);
+        game.p2_commitment = Some(p2_commitment);
+        begin_reveals(&env, &mut game)?;
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // With a verifier configured the blob goes to it as is; it failing to answer counts as a rejection.
+    pub fn reveal_number(env: Env, session_id: u32, player: Address, number: u32, proof_blob: Bytes) -> Result<GamePublicView, Error> {
+        let opens = Self::proof_opens(&env, session_id, &player, number, &proof_blob)?;
+        Self::apply_reveal(env.clone(), session_id, player.clone(), number, opens)
+    }
+
+    // `reveal_number`, then, if that resolved the ball and the innings goes on, `commit_number` of
+    // `next_commitment` for the next ball, so a player can pipeline consecutive balls. A reveal that waits on
+    // the opponent's, ends the innings or ends the game drops the commitment; see `PipelinedReveal`.
+    pub fn reveal_and_commit_next(env: Env, session_id: u32, player: Address, number: u32, proof_blob: Bytes, next_commitment: BytesN<32>) -> Result<PipelinedReveal, Error> {
+        let (before, _) = read_game(&env, session_id)?;
+        let game = Self::reveal_number(env.clone(), session_id, player.clone(), number, proof_blob)?;
+        if before.phase != Phase::BallReveal || game.phase != Phase::BallCommit || game.innings != before.innings {
+            return Ok(PipelinedReveal { game, next_committed: false });
+        }
+        let game = Self::apply_commit(&env, session_id, &player, next_commitment)?;
+        Ok(PipelinedReveal { game, next_committed: true })
+    }
+
+    // Commits the player to their numbers for up to `leaf_count` balls of this innings, from this one on. `root`
+    // is the Merkle root of their leaves (see `merkle_leaf`) and stands as their commitment each ball, so from
+    // then on they only reveal, one leaf per ball through `reveal_leaf`. Leaves left when the innings ends are
+    // discarded.
+    pub fn commit_merkle(env: Env, session_id: u32, player: Address, root: BytesN<32>, leaf_count: u32) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        // The toss and super overs are committed to ball by ball.
+        if game.phase != Phase::BallCommit { return Err(Error::WrongPhase); }
+        if leaf_count == 0 || leaf_count > MAX_MERKLE_LEAVES { return Err(Error::LeafCountInvalid); }
+        let key = DataKey::MerkleCommitment(session_id, player.clone());
+        if env.storage().temporary().has(&key) { return Err(Error::AlreadyCommitted); }
+        env.storage().temporary().set(&key, &MerkleCommitment { root: root.clone(), leaf_count, next_index: 0, innings: game.innings });
+        extend_with_game_ttl(&env, &key);
+        // Copying the opponent's root is refused here as a copied commitment is.
+        Self::apply_commit(&env, session_id, &player, root)
+    }
+
+    pub fn get_merkle_commitment(env: Env, session_id: u32, player: Address) -> Option<MerkleCommitment> {
+        env.storage().temporary().get(&DataKey::MerkleCommitment(session_id, player))
+    }
+
+    // Opens the leaf of the player's `commit_merkle` root for this ball, otherwise as `reveal_number`.
+    // `proof_path` runs from the leaf's sibling up; see `merkle_root`. Leaves open strictly in order, one per
+    // ball: skipping one would let the player choose among their numbers.
+    pub fn reveal_leaf(env: Env, session_id: u32, player: Address, number: u32, salt: BytesN<32>, index: u32, proof_path: Vec<BytesN<32>>) -> Result<GamePublicView, Error> {
+        let (game, _) = read_game(&env, session_id)?;
+        let key = DataKey::MerkleCommitment(session_id, player.clone());
+        let mut merkle: MerkleCommitment = env.storage().temporary().get(&key)
+            .filter(|merkle: &MerkleCommitment| merkle.innings == game.innings)
+            .ok_or(Error::NoMerkleRoot)?;
+        let revealed = if player == game.player1 { game.p1_number } else if player == game.player2 { game.p2_number } else { None };
+        if revealed.is_some() { return Err(Error::AlreadyRevealed); }
+        if index != merkle.next_index || index >= merkle.leaf_count { return Err(Error::LeafOutOfOrder); }
+        let (root, depth) = (merkle.root.clone(), merkle_depth(merkle.leaf_count));
+        // Spent before the reveal, so a ball it resolves doesn't stand the root in again for a leaf it lacks.
+        merkle.next_index += 1;
+        env.storage().temporary().set(&key, &merkle);
+        Self::apply_reveal(env.clone(), session_id, player, number, |_, commitment| {
+            *commitment == root && proof_path.len() == depth
+                && merkle_root(&env, merkle_leaf(&env, number, &salt, index), index, &proof_path) == root
+        })
+    }
+
+    // Both reveals for the round at once, mirroring `commit_both`: each player (or their delegate) signs over the
+    // session and their own number and proof, each proof is checked as `reveal_number` checks it, and the round
+    // resolves straight away. Either reveal failing stores neither.
+    pub fn reveal_both(env: Env, session_id: u32, p1_number: u32, p1_proof: Bytes, p2_number: u32, p2_proof: Bytes) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        let (mut game, _) = read_game(&env, session_id)?;
+        let (player1, player2) = (game.player1.clone(), game.player2.clone());
+        for (player, number, proof) in [(&player1, p1_number, &p1_proof), (&player2, p2_number, &p2_proof)] {
+            move_signer(&env, session_id, player).require_auth_for_args(vec![&env, session_id.into_val(&env), number.into_val(&env), proof.into_val(&env)]);
+        }
+        let p1_opens = Self::proof_opens(&env, session_id, &player1, p1_number, &p1_proof)?;
+        let p2_opens = Self::proof_opens(&env, session_id, &player2, p2_number, &p2_proof)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if game.is_practice { return Err(Error::NotPlayer); }
+        match game.phase { Phase::TossReveal | Phase::BallReveal => {} Phase::SuperOver if both_committed(&game) => {} _ => return Err(wrong_phase(&game)), }
+        if game.p1_number.is_some() || game.p2_number.is_some() { return Err(Error::AlreadyRevealed); }
+        check_number(&game, p1_number)?; check_number(&game, p2_number)?;
+        let (Some(c1), Some(c2)) = (&game.p1_commitment, &game.p2_commitment) else { return Err(Error::CommitMissing) };
+        if !p1_opens(&game, c1) || !p2_opens(&game, c2) { return Err(Error::ProofInvalid); }
+
+        game.p1_number = Some(p1_number);
+        game.p2_number = Some(p2_number);
+        game = Self::resolve_round(&env, session_id, game)?;
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // The check `reveal_number` makes of `proof_blob`. With a verifier configured the blob goes to it as is; it
+    // failing to answer counts as a rejection. The built-in check needs a blob it can decode.
+    fn proof_opens<'a>(env: &'a Env, session_id: u32, player: &'a Address, number: u32, proof_blob: &'a Bytes) -> Result<impl Fn(&Game, &BytesN<32>) -> bool + 'a, Error> {
+        let verifier = Self::get_verifier(env.clone()).map(|address| VerifierClient::new(env, &address));
+        let proof: Option<RevealProof> = if verifier.is_none() { Some(decode_reveal(env, proof_blob)?) } else { None };
+        Ok(move |game: &Game, commitment: &BytesN<32>| {
+            let context = reveal_context(env, session_id, game.innings, game.rounds, player);
+            match (&verifier, &proof) {
+                (Some(verifier), _) => matches!(verifier.try_verify(commitment, &number, &context, proof_blob), Ok(Ok(true))),
+                (None, Some(proof)) => check_reveal_proof(env, game.vk_version, commitment, number, &context, proof),
+                (None, None) => false,
+            }
+        })
+    }
+
+    // Opens a commitment made with `salted_commitment`, for clients without a prover.
+    pub fn reveal_with_salt(env: Env, session_id: u32, player: Address, number: u32, salt: BytesN<32>) -> Result<GamePublicView, Error> {
+        Self::apply_reveal(env.clone(), session_id, player.clone(), number, |game, commitment| {
+            salted_commitment(&env, &reveal_context(&env, session_id, game.innings, game.rounds, &player), number, &salt) == *commitment
+        })
+    }
+
+    // `opens` checks the reveal against the player's stored commitment.
+    fn apply_reveal(env: Env, session_id: u32, player: Address, number: u32, opens: impl Fn(&Game, &BytesN<32>) -> bool) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (mut game, _) = read_game(&env, session_id)?;
+        // Likewise for a valid reveal of the number already stored, including the one that finished the game.
+        let (stored, commitment) = if player == game.player1 { (game.p1_number, &game.p1_commitment) } else if player == game.player2 { (game.p2_number, &game.p2_commitment) } else { (None, &None) };
+        if let (Some(stored), Some(commitment)) = (stored, commitment) {
+            if stored == number && opens(&game, commitment) { return Ok(public_view(&env, session_id, &game)); }
+        }
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        match game.phase { Phase::TossReveal | Phase::BallReveal => {} Phase::SuperOver if both_committed(&game) => {} _ => return Err(wrong_phase(&game)), }
+        // The error rolls back the call, so the commitment stays in place for a valid reveal.
+        check_number(&game, number)?;
+
+        if player == game.player1 {
+            if game.p1_number.is_some() { return Err(Error::AlreadyRevealed); }
+            let commitment = game.p1_commitment.as_ref().ok_or(Error::CommitMissing)?;
+            if !opens(&game, commitment) { return Err(Error::ProofInvalid); }
+            game.p1_number = Some(number);
+        } else if player == game.player2 && !game.is_practice {
+            if game.p2_number.is_some() { return Err(Error::AlreadyRevealed); }
+            let commitment = game.p2_commitment.as_ref().ok_or(Error::CommitMissing)?;
+            if !opens(&game, commitment) { return Err(Error::ProofInvalid); }
+            game.p2_number = Some(number);
+        } else { return Err(Error::NotPlayer); }
+        if game.is_practice { game.p2_number = Some(bot_number(&env, &game)); }
+
+        if game.p1_number.is_some() && game.p2_number.is_some() { game = Self::resolve_round(&env, session_id, game)?; }
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Resolves the toss or ball once both numbers are revealed.
+    fn resolve_round(env: &Env, session_id: u32, mut game: Game) -> Result<Game, Error> {
+        match game.phase {
+            Phase::TossReveal => {
+                game = Self::resolve_toss(env, session_id, game)?;
+                if game.is_practice && game.toss_winner.as_ref() == Some(&game.player2) {
+                    let batter = batter_for(&game, &game.player2, env.prng().gen_range::<u64>(0..=1) == 1);
+                    game.batter = Some(batter.clone());
+                    game.phase = Phase::BallCommit;
+                    RoleChosen { session_id, batter, phase: phase_symbol(env, &game.phase) }.publish(env);
+                }
+            }
+            Phase::BallReveal | Phase::SuperOver => { game = Self::resolve_ball(env, session_id, game)?; }
+            _ => {}
+        }
+        if game.phase == Phase::Finished { Self::note_finished(env, session_id, &game); }
+        game.phase_started_ledger = env.ledger().sequence();
+        sync_merkle_commitments(env, session_id, &mut game)?;
+        Ok(game)
+    }
+
+    // Settles a game the players played off-chain, in one call that both sign over the transcript's keccak256.
+    // The transcript is replayed through the same toss and ball resolution as on-chain play, and must finish
+    // on its last ball with the claimed winner. Only games that have not started the toss can be settled this way.
+    pub fn settle_transcript(env: Env, session_id: u32, transcript: Bytes) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase != Phase::TossCommit || game.p1_commitment.is_some() || game.p2_commitment.is_some() { return Err(Error::WrongPhase); }
+        if game.is_practice { return Err(Error::PracticeGame); }
+        let hash: BytesN<32> = env.crypto().keccak256(&transcript).into();
+        let args = vec![&env, session_id.into_val(&env), hash.into_val(&env)];
+        game.player1.require_auth_for_args(args.clone());
+        game.player2.require_auth_for_args(args);
+
+        let len = transcript.len();
+        if len < TRANSCRIPT_HEADER_LEN + 4 { return Err(Error::TranscriptInvalid); }
+        let balls = read_u32_be(&transcript, 12);
+        let ball_bytes = len - TRANSCRIPT_HEADER_LEN - 4;
+        if balls.checked_mul(TRANSCRIPT_BALL_LEN) != Some(ball_bytes) { return Err(Error::TranscriptInvalid); }
+        let range = game.min_number..=game.max_number;
+        let number = |offset: u32| {
+            let n = read_u32_be(&transcript, offset);
+            if range.contains(&n) { Ok(n) } else { Err(Error::TranscriptInvalid) }
+        };
+
+        game.p1_number = Some(number(0)?); game.p2_number = Some(number(4)?);
+        game = Self::resolve_toss(&env, session_id, game)?;
+        let toss_winner = game.toss_winner.clone().ok_or(Error::TranscriptInvalid)?;
+        let bat = match read_u32_be(&transcript, 8) { 0 => false, 1 => true, _ => return Err(Error::TranscriptInvalid) };
+        let batter = batter_for(&game, &toss_winner, bat);
+        game.batter = Some(batter.clone());
+        game.phase = Phase::BallCommit;
+        RoleChosen { session_id, batter, phase: phase_symbol(&env, &game.phase) }.publish(&env);
+
+        for i in 0..balls {
+            if game.phase == Phase::Finished { return Err(Error::TranscriptInvalid); }
+            let at = TRANSCRIPT_HEADER_LEN + i * TRANSCRIPT_BALL_LEN;
+            if read_u32_be(&transcript, at) != i { return Err(Error::TranscriptInvalid); }
+            game.p1_number = Some(number(at + 4)?); game.p2_number = Some(number(at + 8)?);
+            game = Self::resolve_ball(&env, session_id, game)?;
+        }
+        // A mismatch returns an error, which also rolls back the hub's end_game call.
+        let claimed = match read_u32_be(&transcript, len - 4) { 0 => None, 1 => Some(&game.player1), 2 => Some(&game.player2), _ => return Err(Error::TranscriptInvalid) };
+        if game.phase != Phase::Finished || game.winner.as_ref() != claimed { return Err(Error::TranscriptInvalid); }
+
+        Self::note_finished(&env, session_id, &game);
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Settles balls the players played off-chain with the keys from `start_game_with_keys`, in one call anyone may
+    // submit. Both signatures on every move must open `signed_ball_message`, or the call traps, and the moves are
+    // replayed through `resolve_ball` from a ball neither player has committed to, with `ball_index` counting up
+    // from `Game::rounds`. A move whose `p1_batting` misses the switch after an innings ends stops the replay there,
+    // keeping the balls before it; a wrong batter anywhere else rejects the transcript.
+    pub fn settle_signed_balls(env: Env, session_id: u32, balls: Vec<BallMove>) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        let (mut game, _) = read_game(&env, session_id)?;
+        if !matches!(game.phase, Phase::BallCommit | Phase::SuperOver) || game.p1_commitment.is_some() || game.p2_commitment.is_some() { return Err(Error::WrongPhase); }
+        let (Some(p1_key), Some(p2_key)) = (game.p1_key.clone(), game.p2_key.clone()) else { return Err(Error::NoSigningKeys) };
+        if balls.is_empty() { return Err(Error::TranscriptInvalid); }
+
+        let mut innings_ended = false;
+        for ball in balls.iter() {
+            if game.phase == Phase::Finished { return Err(Error::TranscriptInvalid); }
+            if ball.p1_batting != (game.batter.as_ref() == Some(&game.player1)) {
+                if innings_ended { break; }
+                return Err(Error::TranscriptInvalid);
+            }
+            if ball.ball_index != game.rounds { return Err(Error::TranscriptInvalid); }
+            let message = signed_ball_message(&env, session_id, ball.ball_index, ball.p1_number, ball.p2_number);
+            env.crypto().ed25519_verify(&p1_key, &message, &ball.p1_signature);
+            env.crypto().ed25519_verify(&p2_key, &message, &ball.p2_signature);
+            let innings = game.innings;
+            game.p1_number = Some(ball.p1_number); game.p2_number = Some(ball.p2_number);
+            game = Self::resolve_ball(&env, session_id, game)?;
+            innings_ended = game.innings != innings;
+        }
+
+        if game.phase == Phase::Finished { Self::note_finished(&env, session_id, &game); }
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Awards the game to `claimer` when the opponent alone has a move to make and has not made it
+    // within `get_timeout_ledgers` of the last action.
+    pub fn claim_timeout(env: Env, session_id: u32, claimer: Address) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &claimer);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        let opponent = if claimer == game.player1 { game.player2.clone() } else if claimer == game.player2 { game.player1.clone() } else { return Err(Error::NotPlayer) };
+        if pending_action(&game, &claimer) != PendingAction::Wait || pending_action(&game, &opponent) == PendingAction::Wait { return Err(Error::NotOpponentsTurn); }
+        let deadline = timeout_deadline(&env, &game).ok_or(Error::NotOpponentsTurn)?;
+        if env.ledger().sequence() < deadline { return Err(Error::TimeoutNotReached); }
+
+        let player1_won = claimer == game.player1;
+        game.winner = Some(claimer);
+        game.phase = Phase::Finished;
+        game.result_reported = Self::end_on_hub(&env, session_id, &game, Some(player1_won));
+        Self::note_finished(&env, session_id, &game);
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Both players back out of a game whose toss has not resolved. The game is deleted and nobody loses.
+    pub fn cancel_game(env: Env, session_id: u32) -> Result<(), Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        let (game, _) = read_game(&env, session_id)?;
+        if env.storage().temporary().has(&DataKey::SeriesGame(session_id)) { return Err(Error::SeriesGame); }
+        if !matches!(game.phase, Phase::TossCommit | Phase::TossReveal) { return Err(Error::WrongPhase); }
+        game.player1.require_auth();
+        if !game.is_practice { game.player2.require_auth(); }
+        Self::abort_game(&env, session_id, &game);
+        Ok(())
+    }
+
+    // Cancels games abandoned before the toss resolved: those idle for the timeout that neither player can
+    // claim with `claim_timeout`. Unknown, series and still-claimable games are skipped. Anyone may call it;
+    // returns how many went.
+    pub fn expire_stale_games(env: Env, session_ids: Vec<u32>) -> Result<u32, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        if session_ids.len() > MAX_BATCH_READ { return Err(Error::BatchTooLarge); }
+        let timeout = Self::get_timeout_ledgers(env.clone());
+        let mut expired = 0;
+        for session_id in session_ids.iter() {
+            let Ok((game, _)) = read_game(&env, session_id) else { continue };
+            if env.storage().temporary().has(&DataKey::SeriesGame(session_id)) { continue; }
+            if !matches!(game.phase, Phase::TossCommit | Phase::TossReveal) { continue; }
+            if env.ledger().sequence() < game.last_action_ledger.saturating_add(timeout) { continue; }
+            // The practice bot never claims, so only player1 could.
+            let claimable = |claimer: &Address, opponent: &Address| {
+                pending_action(&game, claimer) == PendingAction::Wait && pending_action(&game, opponent) != PendingAction::Wait
+            };
+            if claimable(&game.player1, &game.player2) || (!game.is_practice && claimable(&game.player2, &game.player1)) { continue; }
+            Self::abort_game(&env, session_id, &game);
+            expired += 1;
+        }
+        Ok(expired)
+    }
+
+    // Deletes a game that never got going, handing back escrow or cancelling its hub session.
+    fn abort_game(env: &Env, session_id: u32, game: &Game) {
+        if game.escrow_token.is_some() {
+            release_escrow(env, game, None);
+        } else if !game.is_practice && !game.friendly {
+            Self::hub_cancel_game(env, session_id, game);
+        }
+        env.storage().temporary().remove(&DataKey::Game(session_id));
+        unindex_game(env, session_id, game);
+        let active = Self::active_session_count(env.clone());
+        env.storage().instance().set(&DataKey::ActiveSessions, &active.saturating_sub(1));
+        GameCancelled { session_id }.publish(env);
+    }
+
+    // Ends a game wedged by something the players can't fix, with no winner, and cancels it on the hub so the
+    // stakes are released. Finished games, whatever their result, are left alone.
+    pub fn admin_void_game(env: Env, session_id: u32) -> Result<GamePublicView, Error> {
+        Self::require_admin(&env);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        game.winner = None;
+        game.phase = Phase::Finished;
+        game.voided = true;
+        game.result_reported = true;
+        // Series games are settled through their series, practice and friendly games never had a hub session,
+        // and escrowed games hand the deposits back here.
+        if game.escrow_token.is_some() {
+            release_escrow(&env, &game, None);
+        } else if !game.is_practice && !game.friendly && !env.storage().temporary().has(&DataKey::SeriesGame(session_id)) {
+            Self::hub_cancel_game(&env, session_id, &game);
+        }
+        Self::note_finished(&env, session_id, &game);
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        GameVoided { session_id, admin: Self::get_admin(env.clone()) }.publish(&env);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Resigns the game in any unfinished phase, handing it to the opponent.
+    pub fn concede(env: Env, session_id: u32, player: Address) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        let winner = if player == game.player1 { game.player2.clone() } else if player == game.player2 { game.player1.clone() } else { return Err(Error::NotPlayer) };
+
+        let player1_won = winner == game.player1;
+        game.winner = Some(winner.clone());
+        game.phase = Phase::Finished;
+        game.result_reported = Self::end_on_hub(&env, session_id, &game, Some(player1_won));
+        Self::note_finished(&env, session_id, &game);
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        Conceded { session_id, player, winner }.publish(&env);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Closes the first innings between balls, as if the batter were out on their current score.
+    pub fn declare(env: Env, session_id: u32, player: Address) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if game.phase != Phase::BallCommit { return Err(wrong_phase(&game)); }
+        if game.innings != 1 { return Err(Error::NotFirstInnings); }
+        if player != game.player1 && player != game.player2 { return Err(Error::NotPlayer); }
+        if game.batter.as_ref() != Some(&player) { return Err(Error::NotBatter); }
+
+        let (score, bowler) = if player == game.player1 { (game.p1_score, game.player2.clone()) } else { (game.p2_score, game.player1.clone()) };
+        game.target = handicapped_target(&game, score + 1);
+        game.innings = 2;
+        game.batter = Some(bowler);
+        game.balls_bowled = 0;
+        game.p1_commitment = None; game.p2_commitment = None;
+        game.p1_number = None; game.p2_number = None;
+        sync_merkle_commitments(&env, session_id, &mut game)?;
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, session_id, &game);
+        Declared { session_id, player, score, target: game.target }.publish(&env);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    fn note_finished(env: &Env, session_id: u32, game: &Game) {
+        let active = Self::active_session_count(env.clone());
+        env.storage().instance().set(&DataKey::ActiveSessions, &active.saturating_sub(1));
+        unindex_game(env, session_id, game);
+        write_result(env, session_id, &game_result(game, env.ledger().sequence()));
+        Self::advance_series(env, session_id, game);
+        // Practice and voided games leave the record alone.
+        if !game.is_practice && !game.voided { stats::record_finished(env, session_id, game); }
+        GameEnded {
+            session_id, winner: game.winner.clone(), p1_score: game.p1_score, p2_score: game.p2_score,
+            phase: phase_symbol(env, &game.phase), result: result_symbol(env, game),
+        }.publish(env);
+        publish_watched(env, session_id, game, symbol_short!("finished"));
+    }
+
+    pub fn choose_role(env: Env, session_id: u32, player: Address, bat: bool) -> Result<GamePublicView, Error> {
+        Self::apply_role_choice(env, session_id, player, bat, None)
+    }
+
+    // `choose_role` plus the toss winner's commitment for the first ball, under one auth.
+    pub fn choose_role_and_commit(env: Env, session_id: u32, player: Address, bat: bool, commitment: BytesN<32>) -> Result<GamePublicView, Error> {
+        Self::apply_role_choice(env, session_id, player, bat, Some(commitment))
+    }
+
+    fn apply_role_choice(env: Env, session_id: u32, player: Address, bat: bool, commitment: Option<BytesN<32>>) -> Result<GamePublicView, Error> {
+        assert_not_retired(&env);
+        ensure_migrated(&env)?;
+        require_move_auth(&env, session_id, &player);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase != Phase::BatBowlChoice { return Err(wrong_phase(&game)); }
+        let toss_winner = game.toss_winner.as_ref().ok_or(Error::WrongPhase)?;
+        if &player != toss_winner { return Err(Error::NotTossWinner); }
+
+        let batter = batter_for(&game, &player, bat);
+        game.batter = Some(batter.clone());
+
+        game.p1_commitment = None; game.p2_commitment = None;
+        game.p1_number = None; game.p2_number = None;
+        if player == game.player1 { game.p1_commitment = commitment; } else { game.p2_commitment = commitment; }
+        game.phase = Phase::BallCommit;
+        RoleChosen { session_id, batter, phase: phase_symbol(&env, &game.phase) }.publish(&env);
+        game.phase_started_ledger = env.ledger().sequence();
+        game.last_action_ledger = env.ledger().sequence();
+
+        write_game(&env, session_id, &game);
+        Ok(public_view(&env, session_id, &game))
+    }
+
+    // Lets `delegate` submit `player`'s commits, reveals and role choices, for one session or (`None`) all of
+    // them. Starting games still needs the player. Approving the same scope again replaces its delegate.
+    pub fn approve_delegate(env: Env, player: Address, delegate: Address, session_id: Option<u32>) -> Result<(), Error> {
+        assert_not_retired(&env);
+        player.require_auth();
+        let mut delegations = delegations(&env, &player);
+        match delegations.iter().position(|d| d.session_id == session_id) {
+            Some(i) => delegations.set(i as u32, Delegation { delegate, session_id }),
+            None => {
+                if delegations.len() >= MAX_DELEGATIONS { return Err(Error::DelegationLimit); }
+                delegations.push_back(Delegation { delegate, session_id });
+            }
+        }
+        Self::write_delegations(&env, &player, &delegations);
+        Ok(())
+    }
+
+    pub fn revoke_delegate(env: Env, player: Address, delegate: Address, session_id: Option<u32>) -> Result<(), Error> {
+        assert_not_retired(&env);
+        player.require_auth();
+        let mut delegations = delegations(&env, &player);
+        let i = delegations.first_index_of(Delegation { delegate, session_id }).ok_or(Error::DelegationNotFound)?;
+        delegations.remove(i);
+        Self::write_delegations(&env, &player, &delegations);
+        Ok(())
+    }
+
+    pub fn get_delegations(env: Env, player: Address) -> Vec<Delegation> { delegations(&env, &player) }
+
+    // Unfinished sessions `player` is in, oldest first, at most MAX_ACTIVE_GAMES. Games that expired
+    // unfinished are left out. Empty for players who have opted out of player-keyed records.
+    pub fn get_games_for_player(env: Env, player: Address) -> Vec<u32> {
+        live_sessions(&env, &player_games(&env, &player))
+    }
+
+    fn write_delegations(env: &Env, player: &Address, delegations: &Vec<Delegation>) {
+        let key = DataKey::Delegations(player.clone());
+        if delegations.is_empty() { env.storage().persistent().remove(&key); return; }
+        env.storage().persistent().set(&key, delegations);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+    }
+
+    // Names are cosmetic: nothing in the contract identifies a player by name.
+    pub fn set_display_name(env: Env, player: Address, name: Symbol) -> Result<(), Error> {
+        assert_not_retired(&env);
+        player.require_auth();
+        if symbol_len(&env, &name) > MAX_NAME_LEN { return Err(Error::NameTooLong); }
+        let key = DataKey::Name(player);
+        env.storage().persistent().set(&key, &name);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        Ok(())
+    }
+
+    pub fn get_display_name(env: Env, player: Address) -> Option<Symbol> { display_name(&env, &player) }
+
+    // Moderation by the operator.
+    pub fn clear_display_name(env: Env, player: Address) {
+        Self::require_operator(&env);
+        env.storage().persistent().remove(&DataKey::Name(player));
+    }
+
+    // Blocked addresses cannot start games. Blocking is reversible and every change is logged.
+    pub fn block_address(env: Env, addr: Address, reason: u32) {
+        Self::require_operator(&env);
+        let key = DataKey::Blocked(addr.clone());
+        env.storage().persistent().set(&key, &reason);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        Self::log_moderation(&env, addr, symbol_short!("block"), reason);
+    }
+
+    pub fn unblock_address(env: Env, addr: Address) {
+        Self::require_operator(&env);
+        env.storage().persistent().remove(&DataKey::Blocked(addr.clone()));
+        Self::log_moderation(&env, addr, symbol_short!("unblock"), 0);
+    }
+
+    pub fn is_blocked(env: Env, addr: Address) -> bool { is_blocked(&env, &addr) }
+
+    // Page 0 holds the oldest retained entries.
+    pub fn get_moderation_log(env: Env, addr: Address, page: u32) -> Vec<ModerationEntry> {
+        let log = moderation_log(&env, &addr);
+        let start = page.saturating_mul(MODERATION_PAGE_SIZE).min(log.len());
+        log.slice(start..start.saturating_add(MODERATION_PAGE_SIZE).min(log.len()))
+    }
+
+    fn log_moderation(env: &Env, subject: Address, action: Symbol, reason: u32) {
+        let actor = Self::get_operator(env.clone());
+        let mut log = moderation_log(env, &subject);
+        if log.len() >= MAX_MODERATION_LOG { log.pop_front(); }
+        log.push_back(ModerationEntry { actor: actor.clone(), action: action.clone(), reason, ledger: env.ledger().sequence() });
+        let key = DataKey::ModerationLog(subject.clone());
+        env.storage().persistent().set(&key, &log);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        Moderated { subject, action, reason, actor }.publish(env);
+    }
+
+    // One emote per player per round, during an unfinished game.
+    pub fn send_emote(env: Env, session_id: u32, player: Address, emote: u32) -> Result<(), Error> {
+        assert_not_retired(&env);
+        require_move_auth(&env, session_id, &player);
+        let (game, _) = read_game(&env, session_id)?;
+        if player != game.player1 && player != game.player2 { return Err(Error::NotPlayer); }
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if !(1..=MAX_EMOTE).contains(&emote) { return Err(Error::InvalidEmote); }
+        let key = DataKey::Emote(session_id, player.clone());
+        if env.storage().temporary().get(&key) == Some(game.rounds) { return Err(Error::EmoteRateLimited); }
+        env.storage().temporary().set(&key, &game.rounds);
+        extend_with_game_ttl(&env, &key);
+        Emote { session_id, sender: player, emote }.publish(&env);
+        Ok(())
+    }
+
+    // Keeps a slow game from expiring between moves: refreshes the game entry to the configured TTL, and the
+    // players' game indexes, without touching the game itself.
+    pub fn extend_game_ttl(env: Env, session_id: u32, player: Address) -> Result<(), Error> {
+        assert_not_retired(&env);
+        require_move_auth(&env, session_id, &player);
+        let (game, _) = read_game(&env, session_id)?;
+        if player != game.player1 && player != game.player2 { return Err(Error::NotPlayer); }
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        extend_with_game_ttl(&env, &DataKey::Game(session_id));
+        for indexed in [game.player1, game.player2] {
+            let key = DataKey::PlayerGames(indexed);
+            if env.storage().persistent().has(&key) {
+                env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+            }
+        }
+        Ok(())
+    }
+
+    // Deletes every record keyed by `player` and opts them out of future ones. Games and anything
+    // shared with opponents stay.
+    pub fn purge_my_data(env: Env, player: Address) {
+        assert_not_retired(&env);
+        player.require_auth();
+        env.storage().persistent().remove(&DataKey::Name(player.clone()));
+        env.storage().persistent().remove(&DataKey::Delegations(player.clone()));
+        env.storage().persistent().remove(&DataKey::PlayerGames(player.clone()));
+        stats::forget(&env, &player);
+        Self::write_stats_opt_out(&env, &player, true);
+    }
+
+    pub fn set_stats_opt_out(env: Env, player: Address, opt_out: bool) {
+        assert_not_retired(&env);
+        player.require_auth();
+        Self::write_stats_opt_out(&env, &player, opt_out);
+    }
+
+    pub fn get_stats_opt_out(env: Env, player: Address) -> bool {
+        env.storage().persistent().has(&DataKey::StatsOptOut(player))
+    }
+
+    fn write_stats_opt_out(env: &Env, player: &Address, opt_out: bool) {
+        let key = DataKey::StatsOptOut(player.clone());
+        if !opt_out { env.storage().persistent().remove(&key); return; }
+        env.storage().persistent().set(&key, &true);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+    }
+
+    // Readable for as long as the persistent entry lives, after the game itself has expired. Games that
+    // finished before results were recorded are answered from the game while it lasts.
+    pub fn get_result(env: Env, session_id: u32) -> Result<GameResult, Error> {
+        if let Some(result) = env.storage().persistent().get(&DataKey::Result(session_id)) { return Ok(result); }
+        let (game, _) = read_game(&env, session_id)?;
+        if game.phase != Phase::Finished { return Err(Error::GameNotFinished); }
+        Ok(game_result(&game, game.phase_started_ledger))
+    }
+
+    // See `interface::HandCricketResults`. Read from the live game entry, so it lasts as long as that does.
+    pub fn attest_result(env: Env, session_id: u32) -> Result<interface::ResultAttestation, Error> {
+        let (game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished && game.winner.is_none() { return Err(Error::GameDrawn); }
+        let winner = game.winner.ok_or(Error::GameNotFinished)?;
+        // Still open to dispute.
+        if Self::get_pending_settlement(env.clone(), session_id).is_some() { return Err(Error::SettlementPending); }
+        Ok(interface::ResultAttestation {
+            player1: game.player1,
+            player2: game.player2,
+            winner,
+            p1_score: game.p1_score,
+            p2_score: game.p2_score,
+            // The Finished phase starts on the deciding ledger.
+            finished_ledger: game.phase_started_ledger,
+        })
+    }
+
+    // Watching again is a no-op; only the count of watchers per subject is readable.
+    pub fn watch_player(env: Env, watcher: Address, subject: Address) {
+        assert_not_retired(&env);
+        watcher.require_auth();
+        let pair = DataKey::Watching(watcher, subject.clone());
+        if env.storage().persistent().has(&pair) { return; }
+        env.storage().persistent().set(&pair, &true);
+        env.storage().persistent().extend_ttl(&pair, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        Self::write_watcher_count(&env, &subject, watcher_count(&env, &subject) + 1);
+    }
+
+    pub fn unwatch_player(env: Env, watcher: Address, subject: Address) -> Result<(), Error> {
+        assert_not_retired(&env);
+        watcher.require_auth();
+        let pair = DataKey::Watching(watcher, subject.clone());
+        if !env.storage().persistent().has(&pair) { return Err(Error::NotWatching); }
+        env.storage().persistent().remove(&pair);
+        Self::write_watcher_count(&env, &subject, watcher_count(&env, &subject).saturating_sub(1));
+        Ok(())
+    }
+
+    pub fn get_watcher_count(env: Env, subject: Address) -> u32 { watcher_count(&env, &subject) }
+
+    fn write_watcher_count(env: &Env, subject: &Address, count: u32) {
+        let key = DataKey::Watchers(subject.clone());
+        if count == 0 { env.storage().persistent().remove(&key); return; }
+        env.storage().persistent().set(&key, &count);
+        env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+    }
+
+    // The stored game as is, live commitments and revealed numbers included. Gating it would hide nothing:
+    // the ledger entry is readable by anyone. Spectator and opponent UIs should show `get_game_public`.
+    pub fn get_game(env: Env, session_id: u32) -> Result<Game, Error> {
+        read_game(&env, session_id).map(|(game, _)| game)
+    }
+
+    pub fn get_expected_action(env: Env, session_id: u32, player: Address) -> Result<ExpectedAction, Error> {
+        read_game(&env, session_id).map(|(game, _)| expected_action(&game, &player))
+    }
+
+    pub fn get_pending_action(env: Env, session_id: u32) -> Result<Turn, Error> {
+        read_game(&env, session_id).map(|(game, _)| turn(&env, &game))
+    }
+
+    pub fn get_phase_symbol(env: Env, session_id: u32) -> Result<Symbol, Error> {
+        read_game(&env, session_id).map(|(game, _)| phase_symbol(&env, &game.phase))
+    }
+
+    pub fn get_result_symbol(env: Env, session_id: u32) -> Result<Symbol, Error> {
+        read_game(&env, session_id).map(|(game, _)| result_symbol(&env, &game))
+    }
+
+    pub fn get_game_public(env: Env, session_id: u32) -> Result<GamePublicView, Error> {
+        read_game(&env, session_id).map(|(game, _)| public_view(&env, session_id, &game))
+    }
+
+    // `get_game_public` for up to MAX_BATCH_READ sessions, in order; unknown or expired ones are None.
+    pub fn get_games(env: Env, session_ids: Vec<u32>) -> Result<Vec<Option<GamePublicView>>, Error> {
+        if session_ids.len() > MAX_BATCH_READ { return Err(Error::BatchTooLarge); }
+        let mut views = Vec::new(&env);
+        for session_id in session_ids.iter() {
+            views.push_back(read_game(&env, session_id).ok().map(|(game, _)| public_view(&env, session_id, &game)));
+        }
+        Ok(views)
+    }
+
+    // Permissionless: rewrites a game stored in an older layout. Returns whether it needed it.
+    pub fn migrate_game(env: Env, session_id: u32) -> Result<bool, Error> {
+        assert_not_retired(&env);
+        let (game, upgraded) = read_game(&env, session_id)?;
+        if upgraded { write_game(&env, session_id, &game); }
+        Ok(upgraded)
+    }
+
+    // Permissionless: deletes a finished game's entry (and its emote markers) ahead of its TTL and drops the id
+    // from both players' indexes. The archived result stays. Returns false for a session that's already gone,
+    // so sweeps don't have to filter first. A result still inside its dispute window keeps its game.
+    pub fn cleanup_game(env: Env, session_id: u32) -> Result<bool, Error> {
+        assert_not_retired(&env);
+        let Ok((game, _)) = read_game(&env, session_id) else { return Ok(false) };
+        if game.phase != Phase::Finished { return Err(Error::WrongPhase); }
+        if !game.result_reported || env.storage().temporary().has(&DataKey::PendingSettlement(session_id)) { return Err(Error::SettlementPending); }
+        let temporary = env.storage().temporary();
+        temporary.remove(&DataKey::Game(session_id));
+        for player in [&game.player1, &game.player2] {
+            temporary.remove(&DataKey::Emote(session_id, player.clone()));
+            temporary.remove(&DataKey::MerkleCommitment(session_id, player.clone()));
+        }
+        unindex_game(&env, session_id, &game);
+        Ok(true)
+    }
+
+    // `cleanup_game` for up to MAX_BATCH_READ sessions, returning how many were removed. Games still in play or
+    // awaiting settlement are skipped, so one such id doesn't fail the sweep.
+    pub fn cleanup_games(env: Env, session_ids: Vec<u32>) -> Result<u32, Error> {
+        if session_ids.len() > MAX_BATCH_READ { return Err(Error::BatchTooLarge); }
+        let mut removed = 0;
+        for session_id in session_ids.iter() {
+            match Self::cleanup_game(env.clone(), session_id) {
+                Ok(true) => removed += 1,
+                Ok(false) | Err(Error::WrongPhase | Error::SettlementPending) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(removed)
+    }
+
+    pub fn debug_dump(env: Env, session_id: u32) -> Result<DebugReport, Error> {
+        require_feature(&env, features::DEBUG_DUMP)?;
+        let game = Self::get_game(env.clone(), session_id)?;
+        let now = env.ledger().sequence();
+        let mut pending_settlement = Vec::new(&env);
+        if let Some(pending) = Self::get_pending_settlement(env.clone(), session_id) { pending_settlement.push_back(pending); }
+        Ok(DebugReport {
+            failed_invariants: validate_invariants(&env, &game),
+            player1_action: pending_action(&game, &game.player1),
+            player2_action: pending_action(&game, &game.player2),
+            ledgers_in_phase: now.saturating_sub(game.phase_started_ledger),
+            ttl_remaining: (game.last_action_ledger + game_ttl(&env)).saturating_sub(now),
+            timeout_deadline: timeout_deadline(&env, &game),
+            pending_settlement,
+            game,
+        })
+    }
+
+    pub fn get_features(_env: Env) -> u32 {
+        features::FEATURES
+    }
+
+    pub fn supports(_env: Env, feature: Symbol) -> bool {
+        features::bit_for(&feature).is_some_and(|bit| features::FEATURES & bit != 0)
+    }
+
+    // The subset of `get_features` currently switched on.
+    pub fn get_enabled_features(env: Env) -> u32 { enabled_features(&env) }
+
+    pub fn enable_feature(env: Env, bit: u32) -> Result<(), Error> {
+        Self::require_operator(&env);
+        Self::set_feature_toggle(&env, bit, true)
+    }
+    pub fn disable_feature(env: Env, bit: u32) -> Result<(), Error> {
+        Self::require_operator(&env);
+        Self::set_feature_toggle(&env, bit, false)
+    }
+
+    // Only single, compiled-in, non-core bits can be toggled.
+    fn set_feature_toggle(env: &Env, bit: u32, on: bool) -> Result<(), Error> {
+        if bit.count_ones() != 1 || bit & features::FEATURES & features::TOGGLEABLE == 0 { return Err(Error::FeatureNotToggleable); }
+        let toggles: u32 = env.storage().instance().get(&DataKey::FeatureToggles).unwrap_or(features::FEATURES);
+        let toggles = if on { toggles | bit } else { toggles & !bit };
+        env.storage().instance().set(&DataKey::FeatureToggles, &toggles);
+        Ok(())
+    }
+
+    // With no key active the game starts unpinned: salted and verifier reveals still open its commitments,
+    // and proof blobs fail until a key is added.
+    #[cfg(feature = "zk")]
+    fn select_vk_version(env: &Env) -> Result<Option<u32>, Error> {
+        let accept_any: bool = env.storage().instance().get(&DataKey::AcceptAnyVk).unwrap_or(false);
+        Ok(if accept_any { None } else { active_vk_versions(env).iter().max() })
+    }
+
+    #[cfg(not(feature = "zk"))]
+    fn select_vk_version(_env: &Env) -> Result<Option<u32>, Error> {
+        Ok(None)
+    }
+
+    fn resolve_toss(env: &Env, session_id: u32, mut game: Game) -> Result<Game, Error> {
+        let (p1_num, p2_num) = (game.p1_number.unwrap_or(0), game.p2_number.unwrap_or(0));
+        check_number(&game, p1_num)?; check_number(&game, p2_num)?;
+        let player1_wins = toss_player1_wins(game.player1_is_odd, p1_num, p2_num);
+        let toss_winner = if player1_wins { game.player1.clone() } else { game.player2.clone() };
+        game.toss_winner = Some(toss_winner.clone());
+        game.p1_number = None; game.p2_number = None;
+        game.p1_commitment = None; game.p2_commitment = None;
+        game.phase = Phase::BatBowlChoice;
+        TossResolved { session_id, p1_number: p1_num, p2_number: p2_num, toss_winner, phase: phase_symbol(env, &game.phase) }.publish(env);
+        game.rounds += 1;
+        Ok(game)
+    }
+
+    fn resolve_ball(env: &Env, session_id: u32, mut game: Game) -> Result<Game, Error> {
+        if game.innings > 2 { return Self::resolve_super_over_ball(env, session_id, game); }
+        let batter = game.batter.clone().ok_or(Error::WrongPhase)?;
+        let p1_batting = batter == game.player1;
+        let bowler = if p1_batting { game.player2.clone() } else { game.player1.clone() };
+        let (p1_num, p2_num) = (game.p1_number.unwrap_or(0), game.p2_number.unwrap_or(0));
+        check_number(&game, p1_num)?; check_number(&game, p2_num)?;
+        let (bat_num, bowl_num) = if p1_batting { (p1_num, p2_num) } else { (p2_num, p1_num) };
+        let score = if p1_batting { game.p1_score } else { game.p2_score };
+        let (innings, ball) = (game.innings, game.rounds);
+        game.rounds += 1;
+
+        game.balls_bowled += 1;
+
+        let mut outcome = ball_outcome(game.innings, game.target, score, bat_num, bowl_num);
+        let runs = match outcome { BallOutcome::Runs(new) | BallOutcome::ChaseComplete(new) => new - score, _ => 0 };
+        let out = matches!(outcome, BallOutcome::InningsBreak { .. } | BallOutcome::ChaseFailed | BallOutcome::ChaseTied);
+        // The last ball's runs count before the innings closes on the limit.
+        if let BallOutcome::Runs(new) = outcome {
+            if game.max_balls_per_innings > 0 && game.balls_bowled >= game.max_balls_per_innings {
+                if p1_batting { game.p1_score = new; } else { game.p2_score = new; }
+                outcome = if game.innings == 1 {
+                    BallOutcome::InningsBreak { target: new + 1 }
+                } else if new + 1 == game.target {
+                    BallOutcome::ChaseTied
+                } else {
+                    BallOutcome::ChaseFailed
+                };
+            }
+        }
+        // Credited as it falls, since a game that later ends by concession or timeout can't say how its innings ended.
+        if out { stats::record_wicket(env, &game, &bowler); }
+        match outcome {
+            BallOutcome::Runs(score) => {
+                if p1_batting { game.p1_score = score; } else { game.p2_score = score; }
+            }
+            BallOutcome::InningsBreak { target } => {
+                game.target = handicapped_target(&game, target);
+                game.innings = 2;
+                game.batter = Some(bowler);
+                game.balls_bowled = 0;
+            }
+            BallOutcome::ChaseComplete(score) => {
+                if p1_batting { game.p1_score = score; } else { game.p2_score = score; }
+                game.winner = Some(batter);
+                game.phase = Phase::Finished;
+                game.result_reported = Self::end_on_hub(env, session_id, &game, Some(p1_batting));
+            }
+            BallOutcome::ChaseFailed => {
+                game.winner = Some(bowler);
+                game.phase = Phase::Finished;
+                game.result_reported = Self::end_on_hub(env, session_id, &game, Some(!p1_batting));
+            }
+            BallOutcome::ChaseTied => {
+                let super_over_balls = Self::get_super_over_balls(env.clone());
+                if super_over_balls > 0 {
+                    // The chaser stays in and bats first.
+                    game.innings = 3;
+                    game.super_over = 1;
+                    game.super_over_balls = super_over_balls;
+                    game.balls_bowled = 0;
+                } else {
+                    game.phase = Phase::Finished;
+                    game.result_reported = Self::end_on_hub(env, session_id, &game, None);
+                }
+            }
+        }
+        let phase = match game.phase {
+            Phase::Finished => Phase::Finished,
+            _ if game.innings > 2 => Phase::SuperOver,
+            _ => Phase::BallCommit,
+        };
+        BallPlayed {
+            session_id, innings, ball,
+            batter_number: bat_num, bowler_number: bowl_num, runs, out,
+            p1_score: game.p1_score, p2_score: game.p2_score, target: game.target,
+            phase: phase_symbol(env, &phase),
+        }.publish(env);
+        if game.phase == Phase::Finished { return Ok(game); }
+        game.p1_commitment = None; game.p2_commitment = None;
+        game.p1_number = None; game.p2_number = None;
+        game.phase = phase;
+        Ok(game)
+    }
+
+    // Each side bats `super_over_balls` balls or until out, the second stopping as soon as it is ahead.
+    // The higher score wins; level again starts another super over in the same batting order.
+    fn resolve_super_over_ball(env: &Env, session_id: u32, mut game: Game) -> Result<Game, Error> {
+        let batter = game.batter.clone().ok_or(Error::WrongPhase)?;
+        let p1_batting = batter == game.player1;
+        let bowler = if p1_batting { game.player2.clone() } else { game.player1.clone() };
+        let (p1_num, p2_num) = (game.p1_number.unwrap_or(0), game.p2_number.unwrap_or(0));
+        check_number(&game, p1_num)?; check_number(&game, p2_num)?;
+        let (bat_num, bowl_num) = if p1_batting { (p1_num, p2_num) } else { (p2_num, p1_num) };
+        let (innings, ball) = (game.innings, game.rounds);
+        game.rounds += 1;
+        game.balls_bowled += 1;
+
+        let out = bat_num == bowl_num;
+        let runs = if out { 0 } else { bat_num };
+        if out { stats::record_wicket(env, &game, &bowler); }
+        if p1_batting { game.p1_super_score += runs; } else { game.p2_super_score += runs; }
+        let (bat_score, bowl_score) = if p1_batting { (game.p1_super_score, game.p2_super_score) } else { (game.p2_super_score, game.p1_super_score) };
+        let half_over = out || game.balls_bowled >= game.super_over_balls;
+        let winner = match innings {
+            4 if bat_score > bowl_score => Some(batter),
+            4 if half_over && bat_score < bowl_score => Some(bowler.clone()),
+            _ => None,
+        };
+        let phase = if winner.is_some() { Phase::Finished } else { Phase::SuperOver };
+        BallPlayed {
+            session_id, innings, ball,
+            batter_number: bat_num, bowler_number: bowl_num, runs, out,
+            p1_score: game.p1_super_score, p2_score: game.p2_super_score,
+            target: if innings == 4 { bowl_score + 1 } else { 0 },
+            phase: phase_symbol(env, &phase),
+        }.publish(env);
+        if let Some(winner) = winner {
+            game.winner = Some(winner);
+            game.phase = Phase::Finished;
+            let player1_won = game.winner == Some(game.player1.clone());
+            game.result_reported = Self::end_on_hub(env, session_id, &game, Some(player1_won));
+            return Ok(game);
+        }
+        if half_over {
+            game.innings = if innings == 3 { 4 } else { 3 };
+            game.batter = Some(bowler);
+            game.balls_bowled = 0;
+            if innings == 4 {
+                game.super_over += 1;
+                game.p1_super_score = 0; game.p2_super_score = 0;
+            }
+        }
+        game.p1_commitment = None; game.p2_commitment = None;
+        game.p1_number = None; game.p2_number = None;
+        Ok(game)
+    }
+
+    // Practice and friendly games never had a hub session, and escrowed games pay out straight away, with no
+    // dispute window. Returns the game's `result_reported`.
+    fn end_on_hub(env: &Env, session_id: u32, game: &Game, player1_won: Option<bool>) -> bool {
+        if game.escrow_token.is_some() {
+            release_escrow(env, game, player1_won);
+            true
+        } else if !game.is_practice && !game.friendly {
+            Self::call_end_game(env, session_id, player1_won, Some(game))
+        } else {
+            true
+        }
+    }
+
+    // With a dispute window the result is held back for `finalize` instead of going to the hub now.
+    // `player1_won` is None for a draw, and `game` None for a series, which has no single scorecard.
+    // Returns whether the hub has the result.
+    fn call_end_game(env: &Env, session_id: u32, player1_won: Option<bool>, game: Option<&Game>) -> bool {
+        // Series games report through their series; see `advance_series`.
+        if env.storage().temporary().has(&DataKey::SeriesGame(session_id)) { return true; }
+        let window = Self::get_dispute_window(env.clone());
+        if window > 0 {
+            let key = DataKey::PendingSettlement(session_id);
+            let unlock_ledger = env.ledger().sequence().saturating_add(window);
+            env.storage().temporary().set(&key, &PendingSettlement { player1_won, unlock_ledger });
+            extend_with_game_ttl(env, &key);
+            return false;
+        }
+        Self::report_end_game(env, session_id, player1_won, game)
+    }
+
+    // The hub is told each stake less the fee; see `get_fee_bps`. The fee is kept per session until the
+    // session settles, so a rate change mid-game doesn't alter what was withheld.
+    // A deployment without a hub can't start staked sessions at all, and one whose hub does its own matchmaking
+    // only starts them through `start_from_hub`; see `get_direct_start_allowed`. `hub` None means the configured
+    // hub; returns the hub the session was opened on.
+    fn hub_start_game(env: &Env, hub: Option<Address>, session_id: u32, player1: &Address, player2: &Address, player1_points: i128, player2_points: i128) -> Result<Address, Error> {
+        if !Self::get_direct_start_allowed(env.clone()) { return Err(Error::DirectStartDisabled); }
+        let configured = Self::get_hub(env.clone());
+        let game_hub_addr = match hub {
+            Some(hub) if Some(&hub) != configured.as_ref() && !Self::get_allowed_hubs(env.clone()).contains(&hub) => return Err(Error::HubNotAllowed),
+            Some(hub) => hub,
+            None => configured.ok_or(Error::NoHub)?,
+        };
+        let bps = Self::get_fee_bps(env.clone());
+        let (p1_fee, p2_fee) = (fee_for(player1_points, bps), fee_for(player2_points, bps));
+        let game_hub = GameHubClient::new(env, &game_hub_addr);
+        game_hub.start_game(&env.current_contract_address(), &session_id, player1, player2, &(player1_points - p1_fee), &(player2_points - p2_fee));
+        if p1_fee + p2_fee > 0 {
+            let key = DataKey::SessionFee(session_id);
+            env.storage().persistent().set(&key, &(p1_fee + p2_fee));
+            env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        }
+        Ok(game_hub_addr)
+    }
+
+    // The hub `game` was opened on, or the configured one for a series or a game from before that was recorded.
+    fn session_hub(env: &Env, game: Option<&Game>) -> Address {
+        game.and_then(|game| game.hub.clone())
+            .unwrap_or_else(|| env.storage().instance().get(&DataKey::GameHubAddress).expect("GameHub not set"))
+    }
+
+    // A cancelled session returns the stakes whole, so its fee is dropped rather than accrued. A hub without
+    // `cancel_game` is left to release the stakes itself; see `get_hub_supports_cancel`.
+    fn hub_cancel_game(env: &Env, session_id: u32, game: &Game) {
+        if Self::get_hub_supports_cancel(env.clone()) {
+            GameHubClient::new(env, &Self::session_hub(env, Some(game))).cancel_game(&session_id);
+        }
+        env.storage().persistent().remove(&DataKey::SessionFee(session_id));
+    }
+
+    // Scores are as they stood when the game ended, so a concession or timeout reports the innings unfinished.
+    // A hub that traps or errors leaves the move that ended the game standing: this returns false, the fee stays
+    // unaccrued and the result can be delivered later; see `report_result`.
+    fn report_end_game(env: &Env, session_id: u32, player1_won: Option<bool>, game: Option<&Game>) -> bool {
+        let game_hub = GameHubClient::new(env, &Self::session_hub(env, game));
+        let delivered = match (player1_won, game) {
+            (Some(player1_won), Some(game)) if !Self::get_legacy_hub(env.clone()) => matches!(game_hub.try_end_game_detailed(
+                &session_id, &player1_won, &game.p1_score, &game.p2_score, &game.target, &innings_played(game),
+            ), Ok(Ok(()))),
+            (Some(player1_won), _) => matches!(game_hub.try_end_game(&session_id, &player1_won), Ok(Ok(()))),
+            (None, _) => matches!(game_hub.try_end_game_draw(&session_id), Ok(Ok(()))),
+        };
+        if !delivered { return false; }
+        let key = DataKey::SessionFee(session_id);
+        if let Some(fee) = env.storage().persistent().get::<_, i128>(&key) {
+            env.storage().persistent().remove(&key);
+            let accrued = Self::get_fees_accrued(env.clone());
+            env.storage().instance().set(&DataKey::FeesAccrued, &accrued.saturating_add(fee));
+        }
+        true
+    }
+
+    fn require_admin(env: &Env) {
+        assert_not_retired(env);
+        let admin: Address = env.storage().instance().get(&DataKey::Admin).expect("Admin not set");
+        admin.require_auth();
+    }
+
+    // The operator handles routine maintenance such as exporting games; it defaults to the admin.
+    fn require_operator(env: &Env) {
+        assert_not_retired(env);
+        Self::get_operator(env.clone()).require_auth();
+    }
+
+    // Runs `ops` in order and stops at the first failure, returning its index; earlier ops stay applied.
+    // Each role the batch needs authorizes once up front, as held when the batch starts.
+    pub fn admin_multicall(env: Env, ops: Vec<AdminOp>) -> Option<u32> {
+        assert_not_retired(&env);
+        let needs_operator = ops.iter().any(|op| matches!(op, AdminOp::EnableFeature(_) | AdminOp::DisableFeature(_)));
+        let needs_admin = ops.iter().any(|op| !matches!(op, AdminOp::EnableFeature(_) | AdminOp::DisableFeature(_)));
+        let admin = Self::get_admin(env.clone());
+        let operator = Self::get_operator(env.clone());
+        if needs_admin { admin.require_auth(); }
+        if needs_operator && !(needs_admin && operator == admin) { operator.require_auth(); }
+
+        for (i, op) in ops.into_iter().enumerate() {
+            if Self::apply_admin_op(&env, op).is_err() { return Some(i as u32); }
+        }
+        None
+    }
+
+    fn apply_admin_op(env: &Env, op: AdminOp) -> Result<(), Error> {
+        let instance = env.storage().instance();
+        match op {
+            AdminOp::ProposeAdmin(admin) => instance.set(&DataKey::PendingAdmin, &admin),
+            AdminOp::SetHub(hub) => instance.set(&DataKey::GameHubAddress, &hub),
+            AdminOp::SetOperator(operator) => instance.set(&DataKey::Operator, &operator),
+            AdminOp::SetDeprecated(successor) => {
+                instance.set(&DataKey::Successor, &successor);
+                let announcement = Self::get_announcement(env.clone()).map(|a| a.id);
+                Deprecated { successor, announcement }.publish(env);
+            }
+            AdminOp::SetForwardStarts(forward) => instance.set(&DataKey::ForwardStarts, &forward),
+            AdminOp::SetRollbackWindow(ledgers) => instance.set(&DataKey::RollbackWindow, &ledgers),
+            AdminOp::SetAcceptAnyVk(accept_any) => return Self::store_accept_any_vk(env, accept_any),
+            AdminOp::AddVerifyingKey(version, key) => return Self::insert_verifying_key(env, version, key),
+            AdminOp::RetireVerifyingKey(version) => return Self::mark_vk_retired(env, version),
+            AdminOp::ImportGame(blob) => return Self::import_blob(env.clone(), blob),
+            AdminOp::SetReservationLedgers(ledgers) => instance.set(&DataKey::ReservationLedgers, &ledgers),
+            AdminOp::SetDisputeWindow(ledgers) => instance.set(&DataKey::DisputeWindow, &ledgers),
+            AdminOp::SetTimeoutLedgers(ledgers) => instance.set(&DataKey::TimeoutLedgers, &ledgers),
+            AdminOp::SetVerifier(Some(verifier)) => instance.set(&DataKey::VerifierAddress, &verifier),
+            AdminOp::SetVerifier(None) => instance.remove(&DataKey::VerifierAddress),
+            AdminOp::SetSuperOverBalls(balls) => {
+                if balls > MAX_SUPER_OVER_BALLS { return Err(Error::SuperOverTooLong); }
+                instance.set(&DataKey::SuperOverBalls, &balls);
+            }
+            AdminOp::SetOpenGameLedgers(ledgers) => instance.set(&DataKey::OpenGameLedgers, &ledgers),
+            AdminOp::SetGameTtl(ledgers) => {
+                if !(MIN_GAME_TTL_LEDGERS..=MAX_GAME_TTL_LEDGERS).contains(&ledgers) { return Err(Error::InvalidGameTtl); }
+                instance.set(&DataKey::GameTtl, &ledgers);
+            }
+            AdminOp::SetStakeLimits(limits) => {
+                if limits.min_points < 0 || limits.min_points > limits.max_points { return Err(Error::InvalidStakeLimits); }
+                instance.set(&DataKey::StakeLimits, &limits);
+            }
+            AdminOp::SetRequireEqualStakes(equal) => instance.set(&DataKey::RequireEqualStakes, &equal),
+            AdminOp::SetLegacyHub(legacy) => instance.set(&DataKey::LegacyHub, &legacy),
+            AdminOp::SetHubSupportsCancel(supported) => instance.set(&DataKey::HubSupportsCancel, &supported),
+            AdminOp::SetDirectStartAllowed(allowed) => instance.set(&DataKey::DirectStartAllowed, &allowed),
+            AdminOp::AllowHub(hub) => {
+                let mut hubs = Self::get_allowed_hubs(env.clone());
+                if !hubs.contains(&hub) { hubs.push_back(hub); }
+                instance.set(&DataKey::AllowedHubs, &hubs);
+            }
+            AdminOp::DisallowHub(hub) => {
+                let mut hubs = Self::get_allowed_hubs(env.clone());
+                if let Some(i) = hubs.first_index_of(&hub) { hubs.remove(i); }
+                instance.set(&DataKey::AllowedHubs, &hubs);
+            }
+            AdminOp::SetFeeBps(bps) => {
+                if bps > MAX_FEE_BPS { return Err(Error::FeeTooHigh); }
+                instance.set(&DataKey::FeeBps, &bps);
+            }
+            AdminOp::EnableFeature(bit) => return Self::set_feature_toggle(env, bit, true),
+            AdminOp::DisableFeature(bit) => return Self::set_feature_toggle(env, bit, false),
+        }
+        Ok(())
+    }
+
+    pub fn get_admin(env: Env) -> Address { env.storage().instance().get(&DataKey::Admin).expect("Admin not set") }
+    // Admin transfer is two-step so a mistyped address can't take over: `new_admin` only becomes admin by
+    // signing `accept_admin`. Proposing again replaces the pending address.
+    pub fn propose_admin(env: Env, new_admin: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::PendingAdmin, &new_admin);
+    }
+    pub fn accept_admin(env: Env) -> Result<(), Error> {
+        assert_not_retired(&env);
+        let pending: Address = env.storage().instance().get(&DataKey::PendingAdmin).ok_or(Error::NoPendingAdmin)?;
+        pending.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &pending);
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        Ok(())
+    }
+    pub fn cancel_admin_proposal(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env);
+        if !env.storage().instance().has(&DataKey::PendingAdmin) { return Err(Error::NoPendingAdmin); }
+        env.storage().instance().remove(&DataKey::PendingAdmin);
+        Ok(())
+    }
+    pub fn get_pending_admin(env: Env) -> Option<Address> { env.storage().instance().get(&DataKey::PendingAdmin) }
+    pub fn get_operator(env: Env) -> Address {
+        env.storage().instance().get(&DataKey::Operator).unwrap_or_else(|| Self::get_admin(env.clone()))
+    }
+    pub fn set_operator(env: Env, operator: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::Operator, &operator);
+    }
+    pub fn get_hub(env: Env) -> Option<Address> { env.storage().instance().get(&DataKey::GameHubAddress) }
+    pub fn set_hub(env: Env, new_hub: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::GameHubAddress, &new_hub);
+    }
+
+    // Hubs besides the configured one that `start_game_on_hub` accepts, in the order they were allowed. Games
+    // already under way on a hub keep reporting to it after it is disallowed.
+    pub fn get_allowed_hubs(env: Env) -> Vec<Address> { env.storage().instance().get(&DataKey::AllowedHubs).unwrap_or(Vec::new(&env)) }
+    pub fn allow_hub(env: Env, hub: Address) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::AllowHub(hub))
+    }
+    pub fn disallow_hub(env: Env, hub: Address) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::DisallowHub(hub))
+    }
+
+    // Set for a hub without `end_game_detailed`, which is then told only who won.
+    pub fn get_legacy_hub(env: Env) -> bool { env.storage().instance().get(&DataKey::LegacyHub).unwrap_or(false) }
+    pub fn set_legacy_hub(env: Env, legacy: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::LegacyHub, &legacy);
+    }
+
+    // Cleared for a hub without `cancel_game`, on which the call would trap every cancel and void.
+    pub fn get_hub_supports_cancel(env: Env) -> bool { env.storage().instance().get(&DataKey::HubSupportsCancel).unwrap_or(true) }
+    pub fn set_hub_supports_cancel(env: Env, supported: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::HubSupportsCancel, &supported);
+    }
+
+    // Cleared when the hub is the only way into a staked session: every start that would open the hub session
+    // itself then fails with DirectStartDisabled. Friendly, practice and escrowed games don't involve the hub.
+    pub fn get_direct_start_allowed(env: Env) -> bool { env.storage().instance().get(&DataKey::DirectStartAllowed).unwrap_or(true) }
+    pub fn set_direct_start_allowed(env: Env, allowed: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::DirectStartAllowed, &allowed);
+    }
+    // `new_version` is the CONTRACT_VERSION of the incoming build; `migrate` checks it once that code runs.
+    pub fn upgrade(env: Env, new_wasm_hash: BytesN<32>, new_version: u32, force: bool) -> Result<(), Error> {
+        Self::require_admin(&env);
+        let current = Self::get_code_version(env.clone());
+        if new_version < current && !force { return Err(Error::DowngradeBlocked); }
+        record_upgrade(&env, &new_wasm_hash, new_version);
+        UpgradeScheduled { from_version: current, to_version: new_version, forced: force }.publish(&env);
+        env.deployer().update_current_contract_wasm(new_wasm_hash);
+        Ok(())
+    }
+
+    // Run straight after `upgrade`; games refuse moves until it has. `from_version` is the version being
+    // upgraded from, so a migrate meant for a different starting point fails instead of half-applying.
+    // Stored games need no walk here: older layouts are upgraded when next read, or by `migrate_game`.
+    pub fn migrate(env: Env, from_version: u32) -> Result<(), Error> {
+        Self::require_admin(&env);
+        let pending: Option<u32> = env.storage().instance().get(&DataKey::PendingCodeVersion);
+        if pending != Some(CONTRACT_VERSION) { return Err(Error::VersionMismatch); }
+        let old_version = Self::get_code_version(env.clone());
+        if from_version != old_version { return Err(Error::VersionMismatch); }
+        env.storage().instance().set(&DataKey::CodeVersion, &CONTRACT_VERSION);
+        env.storage().instance().remove(&DataKey::PendingCodeVersion);
+        // Upgrades from before hash tracking left no hash to record.
+        if let Some(wasm_hash) = env.storage().instance().get::<_, BytesN<32>>(&DataKey::CurrentWasmHash) {
+            append_version_record(&env, CONTRACT_VERSION, &wasm_hash);
+        }
+        VersionChanged { old_version, new_version: CONTRACT_VERSION }.publish(&env);
+        Ok(())
+    }
+
+    // Re-applies the wasm that was live before the last upgrade, within the rollback window.
+    pub fn rollback(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env);
+        let previous = record_rollback(&env)?;
+        RolledBack { to_wasm_hash: previous.clone(), to_version: Self::get_code_version(env.clone()) }.publish(&env);
+        env.deployer().update_current_contract_wasm(previous);
+        Ok(())
+    }
+
+    // Only known once an upgrade has gone through this contract; the deployed hash isn't readable on-chain.
+    pub fn get_previous_wasm_hash(env: Env) -> Option<BytesN<32>> { env.storage().instance().get(&DataKey::PreviousWasmHash) }
+
+    // Holds session ids `from..=to` for `organizer`, who alone may start games on them until the
+    // reservation expires. Ranges may not overlap a live reservation.
+    pub fn reserve_sessions(env: Env, organizer: Address, from: u32, to: u32) -> Result<Reservation, Error> {
+        Self::require_operator(&env);
+        if from > to || to - from >= MAX_RESERVATION_SIZE { return Err(Error::InvalidRange); }
+        let mut reservations = active_reservations(&env);
+        if reservations.iter().any(|r| r.from <= to && from <= r.to) { return Err(Error::SessionReserved); }
+        if reservations.len() >= MAX_RESERVATIONS { return Err(Error::InvalidRange); }
+        let expires_ledger = env.ledger().sequence().saturating_add(Self::get_reservation_ledgers(env.clone()));
+        let reservation = Reservation { organizer, from, to, expires_ledger };
+        reservations.push_back(reservation.clone());
+        env.storage().instance().set(&DataKey::Reservations, &reservations);
+        Ok(reservation)
+    }
+
+    pub fn get_reservation(env: Env, session_id: u32) -> Option<Reservation> {
+        active_reservations(&env).iter().find(|r| (r.from..=r.to).contains(&session_id))
+    }
+
+    // Whether a game could be started on `session_id`: nothing is stored under it, or only a finished game
+    // whose result has gone to the hub. Reservations are not considered; they depend on who starts it.
+    pub fn session_available(env: Env, session_id: u32) -> bool {
+        match read_game(&env, session_id) {
+            Ok((game, _)) => game.phase == Phase::Finished && Self::get_pending_settlement(env.clone(), session_id).is_none(),
+            Err(_) => true,
+        }
+    }
+
+    // Allocates a fresh session id that only a game with `creator` as one of its players can start on,
+    // so nobody watching for the start can take the id first.
+    pub fn reserve_session(env: Env, creator: Address) -> u32 {
+        assert_not_retired(&env);
+        creator.require_auth();
+        let session_id = Self::next_free_session(&env);
+        let key = DataKey::CreatorReservation(session_id);
+        let expires_ledger = env.ledger().sequence().saturating_add(CREATOR_RESERVATION_LEDGERS);
+        env.storage().temporary().set(&key, &CreatorReservation { creator, expires_ledger });
+        env.storage().temporary().extend_ttl(&key, CREATOR_RESERVATION_LEDGERS, CREATOR_RESERVATION_LEDGERS);
+        session_id
+    }
+
+    // The next id from FIRST_RESERVED_SESSION up that nothing is stored or held under.
+    fn next_free_session(env: &Env) -> u32 {
+        let mut session_id: u32 = env.storage().instance().get(&DataKey::NextReservedSession).unwrap_or(FIRST_RESERVED_SESSION);
+        while env.storage().temporary().has(&DataKey::Game(session_id))
+            || env.storage().temporary().has(&DataKey::Series(session_id))
+            || creator_reservation(env, session_id).is_some()
+            || env.storage().temporary().has(&DataKey::Challenge(session_id))
+            || env.storage().temporary().has(&DataKey::OpenGame(session_id))
+            || Self::get_reservation(env.clone(), session_id).is_some()
+        {
+            session_id = next_reserved_session(session_id);
+        }
+        env.storage().instance().set(&DataKey::NextReservedSession, &next_reserved_session(session_id));
+        session_id
+    }
+
+    // The id the next `start_game_auto` gets; None once every id in the quarter has been handed out.
+    pub fn get_next_session(env: Env) -> Option<u32> { u32::try_from(next_auto_session(&env)).ok() }
+
+    pub fn get_creator_reservation(env: Env, session_id: u32) -> Option<CreatorReservation> { creator_reservation(&env, session_id) }
+
+    pub fn get_timeout_ledgers(env: Env) -> u32 { env.storage().instance().get(&DataKey::TimeoutLedgers).unwrap_or(DEFAULT_TIMEOUT_LEDGERS) }
+    pub fn set_timeout_ledgers(env: Env, ledgers: u32) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::TimeoutLedgers, &ledgers);
+    }
+
+    // Balls each side gets in a super over; 0 leaves a level chase drawn. A super over in progress keeps its own.
+    pub fn get_super_over_balls(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::SuperOverBalls).unwrap_or(DEFAULT_SUPER_OVER_BALLS)
+    }
+    pub fn set_super_over_balls(env: Env, balls: u32) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::SetSuperOverBalls(balls))
+    }
+
+    // How long a `create_open_game` post waits for a joiner. Posts already up keep their expiry.
+    pub fn get_open_game_ledgers(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::OpenGameLedgers).unwrap_or(DEFAULT_OPEN_GAME_LEDGERS)
+    }
+    pub fn set_open_game_ledgers(env: Env, ledgers: u32) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::OpenGameLedgers, &ledgers);
+    }
+
+    // Ledgers each write extends a game entry (and its series, settlement, emote and open-game entries)
+    // to, between about a day and about 60 days.
+    pub fn get_game_ttl(env: Env) -> u32 { game_ttl(&env) }
+    pub fn set_game_ttl(env: Env, ledgers: u32) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::SetGameTtl(ledgers))
+    }
+
+    // Every stake a start, challenge or open game names must lie within these, both ends included. Negative
+    // stakes are never allowed; by default nothing else is ruled out. Challenges and open games already posted
+    // are checked again against the limits in force when they're taken up.
+    pub fn get_stake_limits(env: Env) -> StakeLimits {
+        env.storage().instance().get(&DataKey::StakeLimits).unwrap_or(StakeLimits { min_points: 0, max_points: i128::MAX })
+    }
+    pub fn set_stake_limits(env: Env, min_points: i128, max_points: i128) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::SetStakeLimits(StakeLimits { min_points, max_points }))
+    }
+
+    // For ladders where both sides must stake the same. Off by default, which leaves uneven stakes to
+    // handicap matches and the like.
+    pub fn get_require_equal_stakes(env: Env) -> bool { env.storage().instance().get(&DataKey::RequireEqualStakes).unwrap_or(false) }
+    pub fn set_require_equal_stakes(env: Env, equal: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::RequireEqualStakes, &equal);
+    }
+
+    // Rake, in basis points of each stake, withheld from what the hub is told at the start of a session and
+    // accrued once it settles. Applies to sessions started after it's set.
+    pub fn get_fee_bps(env: Env) -> u32 { env.storage().instance().get(&DataKey::FeeBps).unwrap_or(0) }
+    pub fn set_fee_bps(env: Env, bps: u32) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::apply_admin_op(&env, AdminOp::SetFeeBps(bps))
+    }
+    pub fn get_fees_accrued(env: Env) -> i128 { env.storage().instance().get(&DataKey::FeesAccrued).unwrap_or(0) }
+    pub fn get_session_fee(env: Env, session_id: u32) -> i128 {
+        env.storage().persistent().get(&DataKey::SessionFee(session_id)).unwrap_or(0)
+    }
+    // Called once the admin has settled the accrued fees with the hub off contract. Returns the amount cleared.
+    pub fn report_fees_collected(env: Env) -> i128 {
+        Self::require_admin(&env);
+        let amount = Self::get_fees_accrued(env.clone());
+        env.storage().instance().remove(&DataKey::FeesAccrued);
+        FeesCollected { amount }.publish(&env);
+        amount
+    }
+
+    pub fn get_verifier(env: Env) -> Option<Address> { env.storage().instance().get(&DataKey::VerifierAddress) }
+    // `None` goes back to the built-in check.
+    pub fn set_verifier(env: Env, verifier: Option<Address>) {
+        Self::require_admin(&env);
+        match verifier {
+            Some(verifier) => env.storage().instance().set(&DataKey::VerifierAddress, &verifier),
+            None => env.storage().instance().remove(&DataKey::VerifierAddress),
+        }
+    }
+
+    pub fn get_dispute_window(env: Env) -> u32 { env.storage().instance().get(&DataKey::DisputeWindow).unwrap_or(0) }
+    pub fn set_dispute_window(env: Env, ledgers: u32) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::DisputeWindow, &ledgers);
+    }
+
+    pub fn get_pending_settlement(env: Env, session_id: u32) -> Option<PendingSettlement> {
+        env.storage().temporary().get(&DataKey::PendingSettlement(session_id))
+    }
+
+    // Anyone may deliver a held-back result once its window has passed.
+    pub fn finalize(env: Env, session_id: u32) -> Result<(), Error> {
+        assert_not_retired(&env);
+        let pending = Self::get_pending_settlement(env.clone(), session_id).ok_or(Error::NoPendingSettlement)?;
+        if env.ledger().sequence() < pending.unlock_ledger { return Err(Error::SettlementPending); }
+        // The game normally outlives its settlement; if it has expired the hub just gets the result.
+        // A hub that fails to take it leaves the settlement pending, so `finalize` can be called again.
+        let game = read_game(&env, session_id).ok().map(|(game, _)| game);
+        if !Self::report_end_game(&env, session_id, pending.player1_won, game.as_ref()) { return Err(Error::HubReportFailed); }
+        env.storage().temporary().remove(&DataKey::PendingSettlement(session_id));
+        if let Some(mut game) = game {
+            game.result_reported = true;
+            write_game(&env, session_id, &game);
+        }
+        SettlementFinalized { session_id, player1_won: pending.player1_won }.publish(&env);
+        Ok(())
+    }
+
+    // Anyone may retry delivering a finished game's result to a hub that failed to take it when the game ended.
+    // Results held for the dispute window go through `finalize` instead.
+    pub fn report_result(env: Env, session_id: u32) -> Result<(), Error> {
+        assert_not_retired(&env);
+        let (mut game, _) = read_game(&env, session_id)?;
+        if game.phase != Phase::Finished { return Err(Error::GameNotFinished); }
+        if game.result_reported { return Err(Error::ResultAlreadyReported); }
+        if env.storage().temporary().has(&DataKey::PendingSettlement(session_id)) { return Err(Error::SettlementPending); }
+        let player1_won = game.winner.as_ref().map(|winner| *winner == game.player1);
+        if !Self::report_end_game(&env, session_id, player1_won, Some(&game)) { return Err(Error::HubReportFailed); }
+        game.result_reported = true;
+        write_game(&env, session_id, &game);
+        Ok(())
+    }
+
+    // Overrides the winner of a game whose result is still inside its dispute window. Scores are left as played.
+    pub fn dispute(env: Env, session_id: u32, player1_won: bool) -> Result<(), Error> {
+        Self::require_operator(&env);
+        let mut pending = Self::get_pending_settlement(env.clone(), session_id).ok_or(Error::NoPendingSettlement)?;
+        if env.ledger().sequence() >= pending.unlock_ledger { return Err(Error::DisputeWindowClosed); }
+        let (mut game, _) = read_game(&env, session_id)?;
+        let previous = game.winner.clone();
+        game.winner = Some(if player1_won { game.player1.clone() } else { game.player2.clone() });
+        write_game(&env, session_id, &game);
+        if game.winner != previous { stats::record_disputed(&env, &game, &previous); }
+        if let Some(mut result) = env.storage().persistent().get::<_, GameResult>(&DataKey::Result(session_id)) {
+            result.winner = game.winner.clone();
+            write_result(&env, session_id, &result);
+        }
+        pending.player1_won = Some(player1_won);
+        env.storage().temporary().set(&DataKey::PendingSettlement(session_id), &pending);
+        Disputed { session_id, operator: Self::get_operator(env.clone()), player1_won }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_reservation_ledgers(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::ReservationLedgers).unwrap_or(DEFAULT_RESERVATION_LEDGERS)
+    }
+    pub fn set_reservation_ledgers(env: Env, ledgers: u32) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::ReservationLedgers, &ledgers);
+    }
+
+    pub fn get_rollback_window(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::RollbackWindow).unwrap_or(DEFAULT_ROLLBACK_WINDOW_LEDGERS)
+    }
+    pub fn set_rollback_window(env: Env, ledgers: u32) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::RollbackWindow, &ledgers);
+    }
+
+    // Oldest first, capped at VERSION_HISTORY_LEN. The initial deployment isn't listed: its hash isn't readable on-chain.
+    pub fn get_version_history(env: Env) -> Vec<VersionRecord> {
+        env.storage().persistent().get(&DataKey::VersionHistory).unwrap_or(Vec::new(&env))
+    }
+
+    // Deployments from before versioning have no CodeVersion and report 0.
+    pub fn get_code_version(env: Env) -> u32 {
+        env.storage().instance().get(&DataKey::CodeVersion).unwrap_or(0)
+    }
+
+    pub fn set_deprecated(env: Env, successor: Address) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::Successor, &successor);
+        let announcement = Self::get_announcement(env.clone()).map(|a| a.id);
+        Deprecated { successor, announcement }.publish(&env);
+    }
+
+    // Replaces the current announcement; ids count up so events and later actions can refer to one.
+    pub fn announce(env: Env, kind: Symbol, starts_at_ledger: u32, message_hash: BytesN<32>) -> u32 {
+        Self::require_operator(&env);
+        let previous: Option<Announcement> = env.storage().instance().get(&DataKey::Announcement);
+        let id = previous.map_or(1, |a| a.id + 1);
+        let announcement = Announcement { id, kind: kind.clone(), starts_at_ledger, message_hash: message_hash.clone() };
+        env.storage().instance().set(&DataKey::Announcement, &announcement);
+        Announced { kind, id, starts_at_ledger, message_hash }.publish(&env);
+        id
+    }
+
+    pub fn get_announcement(env: Env) -> Option<Announcement> {
+        let announcement: Announcement = env.storage().instance().get(&DataKey::Announcement)?;
+        let ends = announcement.starts_at_ledger.saturating_add(ANNOUNCEMENT_DURATION_LEDGERS);
+        if env.ledger().sequence() < ends { Some(announcement) } else { None }
+    }
+
+    // Replaces the deployment's branding. `name` is held to the display name limit.
+    pub fn set_branding(env: Env, name: Symbol, url_hash: BytesN<32>, accent: u32) -> Result<(), Error> {
+        Self::require_operator(&env);
+        if symbol_len(&env, &name) > MAX_NAME_LEN { return Err(Error::NameTooLong); }
+        env.storage().instance().set(&DataKey::Branding, &Branding { name: name.clone(), url_hash: url_hash.clone(), accent });
+        BrandingChanged { name, url_hash, accent }.publish(&env);
+        Ok(())
+    }
+
+    pub fn get_branding(env: Env) -> Option<Branding> { env.storage().instance().get(&DataKey::Branding) }
+
+    // `version` is this build's CONTRACT_VERSION, which `get_code_version` only reports once `migrate` has run.
+    pub fn get_metadata(env: Env) -> Metadata {
+        let contract = Symbol::new(&env, CONTRACT_NAME);
+        let branding = Self::get_branding(env.clone()).unwrap_or(Branding { name: contract.clone(), url_hash: BytesN::from_array(&env, &[0; 32]), accent: 0 });
+        Metadata { contract, version: CONTRACT_VERSION, name: branding.name, url_hash: branding.url_hash, accent: branding.accent }
+    }
+
+    pub fn get_successor(env: Env) -> Option<Address> { env.storage().instance().get(&DataKey::Successor) }
+
+    // Where new games should be started instead; clients check it on Error::Deprecated.
+    pub fn get_redirect(env: Env) -> Option<Address> { Self::get_successor(env) }
+
+    pub fn get_forward_starts(env: Env) -> bool { env.storage().instance().get(&DataKey::ForwardStarts).unwrap_or(false) }
+    pub fn set_forward_starts(env: Env, forward: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::ForwardStarts, &forward);
+    }
+
+    // Games started and not yet finished. Games that expire unfinished stay counted.
+    pub fn active_session_count(env: Env) -> u32 { env.storage().instance().get(&DataKey::ActiveSessions).unwrap_or(0) }
+
+    // Terminal: drops the hub binding and locks every mutating entrypoint. Views keep working.
+    pub fn retire(env: Env) -> Result<(), Error> {
+        Self::require_admin(&env);
+        if !env.storage().instance().has(&DataKey::Successor) { return Err(Error::NotDeprecated); }
+        if Self::active_session_count(env.clone()) > 0 { return Err(Error::LiveGames); }
+        env.storage().instance().remove(&DataKey::GameHubAddress);
+        env.storage().instance().set(&DataKey::Retired, &true);
+        Retired {}.publish(&env);
+        Ok(())
+    }
+
+    // Moves a live game out of a deprecated deployment: the session is removed here and returned as a blob
+    // for `import_game` on the successor.
+    pub fn export_game(env: Env, session_id: u32) -> Result<MigrationBlob, Error> {
+        Self::require_operator(&env);
+        let successor: Address = env.storage().instance().get(&DataKey::Successor).ok_or(Error::NotDeprecated)?;
+        let (game, _) = read_game(&env, session_id)?;
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if env.storage().temporary().has(&DataKey::SeriesGame(session_id)) { return Err(Error::SeriesGame); }
+        // The deposits can't follow the game to the successor.
+        if game.escrow_token.is_some() { return Err(Error::EscrowGame); }
+        let phase = phase_symbol(&env, &game.phase);
+        // Games from before `Game::hub` was recorded settle through the configured hub.
+        let hub = match &game.hub {
+            Some(hub) => Some(hub.clone()),
+            None if game.is_practice || game.friendly => None,
+            None => Some(Self::get_hub(env.clone()).ok_or(Error::NoHub)?),
+        };
+
+        unindex_game(&env, session_id, &game);
+        let blob = MigrationBlob {
+            schema_version: STORAGE_SCHEMA_VERSION,
+            source: env.current_contract_address(),
+            hub,
+            session_id,
+            game: StoredGame::V2(game),
+        };
+        env.storage().temporary().remove(&DataKey::Game(session_id));
+        let active = Self::active_session_count(env.clone());
+        env.storage().instance().set(&DataKey::ActiveSessions, &active.saturating_sub(1));
+        GameExported { session_id, successor, blob_hash: migration_blob_hash(&env, &blob), phase }.publish(&env);
+        Ok(blob)
+    }
+
+    // Recreates an exported session here. Nothing is re-registered with the hub: the session already
+    // exists there and is settled by whichever deployment finishes it, so the blob's hub must be this
+    // deployment's hub or one of `get_allowed_hubs`.
+    pub fn import_game(env: Env, blob: MigrationBlob) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::import_blob(env, blob)
+    }
+
+    fn import_blob(env: Env, blob: MigrationBlob) -> Result<(), Error> {
+        if blob.schema_version > STORAGE_SCHEMA_VERSION { return Err(Error::InvalidSnapshot); }
+        if env.storage().temporary().has(&DataKey::Game(blob.session_id)) { return Err(Error::SessionExists); }
+        if let Some(hub) = &blob.hub {
+            if Self::get_hub(env.clone()).as_ref() != Some(hub) && !Self::get_allowed_hubs(env.clone()).contains(hub) { return Err(Error::HubMismatch); }
+        }
+        let (mut game, _) = upgrade_stored_game(&env, blob.game.clone());
+        if game.phase == Phase::Finished { return Err(Error::GameAlreadyEnded); }
+        if !validate_invariants(&env, &game).is_empty() { return Err(Error::InvalidSnapshot); }
+        if let Some(version) = game.vk_version {
+            if !env.storage().persistent().has(&DataKey::VerifyingKey(version)) { return Err(Error::VerifyingKeyNotFound); }
+        }
+
+        game.last_action_ledger = env.ledger().sequence();
+        write_game(&env, blob.session_id, &game);
+        index_game(&env, blob.session_id, &game.player1)?;
+        if !game.is_practice { index_game(&env, blob.session_id, &game.player2)?; }
+        let active = Self::active_session_count(env.clone());
+        env.storage().instance().set(&DataKey::ActiveSessions, &(active + 1));
+        let phase = phase_symbol(&env, &game.phase);
+        GameImported { session_id: blob.session_id, source: blob.source.clone(), blob_hash: migration_blob_hash(&env, &blob), phase }.publish(&env);
+        Ok(())
+    }
+}
+
+// Verifying key management only exists alongside proof verification.
+#[cfg(feature = "zk")]
+#[contractimpl]
+impl HandCricketContract {
+    pub fn add_verifying_key(env: Env, version: u32, key: Bytes) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::insert_verifying_key(&env, version, key)
+    }
+    // Registers a Groth16 key (layout in `groth16`) under the next unused version and returns it.
+    pub fn set_verification_key(env: Env, vk: Bytes) -> Result<u32, Error> {
+        Self::require_admin(&env);
+        if !groth16::validate_verifying_key(&env, &vk) { return Err(Error::VerifyingKeyInvalid); }
+        let mut version = active_vk_versions(&env).iter().max().unwrap_or(0) + 1;
+        while env.storage().persistent().has(&DataKey::VerifyingKey(version)) { version += 1; }
+        Self::insert_verifying_key(&env, version, vk)?;
+        Ok(version)
+    }
+    pub fn retire_verifying_key(env: Env, version: u32) -> Result<(), Error> {
+        Self::require_admin(&env);
+        Self::mark_vk_retired(&env, version)
+    }
+    pub fn get_verifying_key(env: Env, version: u32) -> Result<VerifyingKey, Error> {
+        env.storage().persistent().get(&DataKey::VerifyingKey(version)).ok_or(Error::VerifyingKeyNotFound)
+    }
+    pub fn get_active_vk_versions(env: Env) -> Vec<u32> { active_vk_versions(&env) }
+    pub fn get_accept_any_vk(env: Env) -> bool { env.storage().instance().get(&DataKey::AcceptAnyVk).unwrap_or(false) }
+    pub fn set_accept_any_vk(env: Env, accept_any: bool) {
+        Self::require_admin(&env);
+        env.storage().instance().set(&DataKey::AcceptAnyVk, &accept_any);
+    }
+
+    fn insert_verifying_key(env: &Env, version: u32, key: Bytes) -> Result<(), Error> {
+        let vk_key = DataKey::VerifyingKey(version);
+        if env.storage().persistent().has(&vk_key) { return Err(Error::VerifyingKeyExists); }
+        // A key with the Groth16 length is verified as one, so it must parse as one.
+        if groth16::is_groth16_key(&key) && !groth16::validate_verifying_key(env, &key) { return Err(Error::VerifyingKeyInvalid); }
+        env.storage().persistent().set(&vk_key, &VerifyingKey { key, retired: false });
+        env.storage().persistent().extend_ttl(&vk_key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+        let mut active = active_vk_versions(env);
+        active.push_back(version);
+        env.storage().instance().set(&DataKey::ActiveVkVersions, &active);
+        Ok(())
+    }
+    fn mark_vk_retired(env: &Env, version: u32) -> Result<(), Error> {
+        let vk_key = DataKey::VerifyingKey(version);
+        let mut vk: VerifyingKey = env.storage().persistent().get(&vk_key).ok_or(Error::VerifyingKeyNotFound)?;
+        if vk.retired { return Err(Error::VerifyingKeyNotFound); }
+        vk.retired = true;
+        env.storage().persistent().set(&vk_key, &vk);
+        let mut active = active_vk_versions(env);
+        if let Some(i) = active.first_index_of(version) { active.remove(i); }
+        env.storage().instance().set(&DataKey::ActiveVkVersions, &active);
+        Ok(())
+    }
+    fn store_accept_any_vk(env: &Env, accept_any: bool) -> Result<(), Error> {
+        env.storage().instance().set(&DataKey::AcceptAnyVk, &accept_any);
+        Ok(())
+    }
+}
+
+// Token escrow only exists with the `escrow` feature.
+#[cfg(feature = "escrow")]
+#[contractimpl]
+impl HandCricketContract {
+    // For deployments without a hub: each player escrows `amount` of `token` with this contract, and the game
+    // pays out itself, the whole pot to the winner and each deposit back on a draw, cancellation or void.
+    // Both players sign over the session, token and amount; the transfers run under that authorization.
+    pub fn start_game_with_token(env: Env, session_id: u32, player1: Address, player2: Address, token: Address, amount: i128) -> Result<(), Error> {
+        require_feature(&env, features::ESCROW)?;
+        Self::check_can_open(&env, session_id, &player1, &player2, None)?;
+        Self::check_stakes(&env, amount, amount)?;
+        let args = vec![&env, session_id.into_val(&env), token.into_val(&env), amount.into_val(&env)];
+        player1.require_auth_for_args(args.clone());
+        player2.require_auth_for_args(args);
+        let token_client = token::TokenClient::new(&env, &token);
+        token_client.transfer(&player1, env.current_contract_address(), &amount);
+        token_client.transfer(&player2, env.current_contract_address(), &amount);
+
+        let player1_is_odd = toss_player1_is_odd(&env, session_id, &player1, &player2);
+        let vk_version = Self::select_vk_version(&env)?;
+        let game = Game { escrow_token: Some(token), ..new_game(&env, player1, player2, amount, amount, player1_is_odd, vk_version) };
+        Self::begin_game(&env, session_id, &game)
+    }
+}
+
+// Best-of-N series only exist with the `tournaments` feature.
+#[cfg(feature = "tournaments")]
+#[contractimpl]
+impl HandCricketContract {
+    // Starts a best-of-N: the hub sees one session, `series_id`, with the given points, and the players play
+    // consecutive games on fresh session ids (see `get_series`) until one of them has won `games_to_win`.
+    // Each game's winner counts for that game only, however it ended; draws count for nobody. Odd and even
+    // swap between games.
+    pub fn start_series(env: Env, series_id: u32, player1: Address, player2: Address, player1_points: i128, player2_points: i128, games_to_win: u32) -> Result<Series, Error> {
+        require_feature(&env, features::TOURNAMENTS)?;
+        Self::check_can_open(&env, series_id, &player1, &player2, None)?;
+        Self::check_stakes(&env, player1_points, player2_points)?;
+        if env.storage().temporary().has(&DataKey::Series(series_id)) { return Err(Error::SessionExists); }
+        if !(1..=MAX_SERIES_WINS).contains(&games_to_win) { return Err(Error::InvalidSeriesLength); }
+        player1.require_auth_for_args(vec![&env, series_id.into_val(&env), player1_points.into_val(&env), games_to_win.into_val(&env)]);
+        player2.require_auth_for_args(vec![&env, series_id.into_val(&env), player2_points.into_val(&env), games_to_win.into_val(&env)]);
+
+        Self::hub_start_game(&env, None, series_id, &player1, &player2, player1_points, player2_points)?;
+
+        let mut series = Series {
+            player1, player2, player1_points, player2_points, games_to_win,
+            sessions: Vec::new(&env), results: Vec::new(&env),
+            p1_wins: 0, p2_wins: 0, winner: None,
+        };
+        Self::start_series_game(&env, series_id, &mut series)?;
+        Ok(series)
+    }
+
+    pub fn get_series(env: Env, series_id: u32) -> Result<Series, Error> {
+        env.storage().temporary().get(&DataKey::Series(series_id)).ok_or(Error::SeriesNotFound)
+    }
+
+    // Permissionless: starts the next game of a series whose previous game finished without one starting,
+    // as when a player's game index was full at the time.
+    pub fn start_next_series_game(env: Env, series_id: u32) -> Result<Series, Error> {
+        assert_not_retired(&env);
+        let mut series = Self::get_series(env.clone(), series_id)?;
+        if series.winner.is_some() || series.results.len() < series.sessions.len() { return Err(Error::NoSeriesGamePending); }
+        Self::start_series_game(&env, series_id, &mut series)?;
+        Ok(series)
+    }
+
+    // Checks everything that can fail before writing, so a failed start leaves the series as it was.
+    fn start_series_game(env: &Env, series_id: u32, series: &mut Series) -> Result<(), Error> {
+        let vk_version = Self::select_vk_version(env)?;
+        if !index_has_room(env, &series.player1) || !index_has_room(env, &series.player2) { return Err(Error::TooManyActiveGames); }
+        let session_id = Self::next_free_session(env);
+        let first_is_odd = toss_player1_is_odd(env, series_id, &series.player1, &series.player2);
+        let player1_is_odd = first_is_odd != series.sessions.len().is_multiple_of(2);
+        let game = new_game(env, series.player1.clone(), series.player2.clone(), series.player1_points, series.player2_points, player1_is_odd, vk_version);
+        series.sessions.push_back(session_id);
+        write_series(env, series_id, series);
+        env.storage().temporary().set(&DataKey::SeriesGame(session_id), &series_id);
+        extend_with_game_ttl(env, &DataKey::SeriesGame(session_id));
+        Self::begin_game(env, session_id, &game)
+    }
+
+    // Records a finished game in its series, then starts the next game or reports the series to the hub.
+    // A next game that can't start is left for `start_next_series_game`, and a result the hub fails to take
+    // is held as a settlement due now, for `finalize` to deliver.
+    fn advance_series(env: &Env, session_id: u32, game: &Game) {
+        let Some(series_id) = env.storage().temporary().get::<_, u32>(&DataKey::SeriesGame(session_id)) else { return };
+        let Ok(mut series) = Self::get_series(env.clone(), series_id) else { return };
+        series.results.push_back(game.winner.clone());
+        match &game.winner {
+            Some(w) if *w == series.player1 => series.p1_wins += 1,
+            Some(_) => series.p2_wins += 1,
+            None => {}
+        }
+        if series.p1_wins.max(series.p2_wins) < series.games_to_win {
+            if Self::start_series_game(env, series_id, &mut series).is_err() { write_series(env, series_id, &series); }
+            return;
+        }
+        let player1_won = series.p1_wins > series.p2_wins;
+        series.winner = Some(if player1_won { series.player1.clone() } else { series.player2.clone() });
+        write_series(env, series_id, &series);
+        let key = DataKey::PendingSettlement(series_id);
+        if !Self::call_end_game(env, series_id, Some(player1_won), None) && !env.storage().temporary().has(&key) {
+            env.storage().temporary().set(&key, &PendingSettlement { player1_won: Some(player1_won), unlock_ledger: env.ledger().sequence() });
+            extend_with_game_ttl(env, &key);
+        }
+    }
+}
+
+// Builds without `tournaments` never start a series game.
+#[cfg(not(feature = "tournaments"))]
+impl HandCricketContract {
+    fn advance_series(_env: &Env, _session_id: u32, _game: &Game) {}
+}
+
+// Lifetime records only exist with the `stats` feature.
+#[cfg(feature = "stats")]
+#[contractimpl]
+impl HandCricketContract {
+    // All zeros for a player who has never finished a game.
+    pub fn get_stats(env: Env, player: Address) -> PlayerStats {
+        stats::player_stats(&env, &player)
+    }
+
+    // At most LEADERBOARD_SIZE players, best first.
+    pub fn get_leaderboard(env: Env) -> Vec<LeaderboardEntry> {
+        stats::leaderboard(&env)
+    }
+
+    // Empties the board; players reappear as they next finish a game.
+    pub fn reset_leaderboard(env: Env) {
+        Self::require_admin(&env);
+        env.storage().persistent().remove(&DataKey::Leaderboard);
+    }
+
+    // `p_a_wins` are `a`'s, whichever seat they played from.
+    pub fn get_head_to_head(env: Env, a: Address, b: Address) -> HeadToHead {
+        let mut record = stats::head_to_head(&env, &a, &b);
+        if a > b { core::mem::swap(&mut record.p_a_wins, &mut record.p_b_wins); }
+        record
+    }
+}
+
+// Builds without `zk` have no verifying keys; batched key operations fail instead.
+#[cfg(not(feature = "zk"))]
+impl HandCricketContract {
+    fn insert_verifying_key(_env: &Env, _version: u32, _key: Bytes) -> Result<(), Error> { Err(Error::FeatureDisabled) }
+    fn mark_vk_retired(_env: &Env, _version: u32) -> Result<(), Error> { Err(Error::FeatureDisabled) }
+    fn store_accept_any_vk(_env: &Env, _accept_any: bool) -> Result<(), Error> { Err(Error::FeatureDisabled) }
+}
+
+pub mod features;
+#[cfg(feature = "zk")]
+mod groth16;
+pub mod interface;
+#[cfg(feature = "stats")]
+mod stats;
+
+// Builds without `stats` keep no records.
+#[cfg(not(feature = "stats"))]
+mod stats {
+    use soroban_sdk::{Address, Env};
+    use crate::Game;
+
+    pub fn record_wicket(_env: &Env, _game: &Game, _bowler: &Address) {}
+    pub fn record_finished(_env: &Env, _session_id: u32, _game: &Game) {}
+    pub fn record_disputed(_env: &Env, _game: &Game, _previous: &Option<Address>) {}
+    pub fn forget(_env: &Env, _player: &Address) {}
+}
+
+#[cfg(any(test, feature = "testutils"))]
+pub mod conformance;
+#[cfg(any(test, feature = "testutils"))]
+pub mod testutils;
+
+// The unit suite drives reveals through proof blobs; tests/core.rs runs under every feature set.
+#[cfg(feature = "zk")]
+mod test;
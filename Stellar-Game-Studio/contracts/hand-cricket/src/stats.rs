@@ -0,0 +1,166 @@
+//! Lifetime player records: per-player stats, head-to-head records and the
+//! leaderboard. Only built with the `stats` cargo feature; without it the
+//! hooks the game calls are no-ops and nothing is stored.
+
+use soroban_sdk::{Address, Env, Vec};
+
+use crate::{enabled_features, features, DataKey, Game, HeadToHead, LeaderboardEntry, PlayerStats, PERSISTENT_TTL_LEDGERS};
+
+const LEADERBOARD_SIZE: u32 = 20;
+
+pub fn player_stats(env: &Env, player: &Address) -> PlayerStats {
+    env.storage().persistent().get(&DataKey::Stats(player.clone())).unwrap_or_default()
+}
+
+// Opted-out players keep no stats.
+fn update_stats(env: &Env, player: &Address, update: impl FnOnce(&mut PlayerStats)) {
+    if env.storage().persistent().has(&DataKey::StatsOptOut(player.clone())) { return; }
+    let mut stats = player_stats(env, player);
+    update(&mut stats);
+    let key = DataKey::Stats(player.clone());
+    env.storage().persistent().set(&key, &stats);
+    env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+fn outcome_count<'a>(stats: &'a mut PlayerStats, player: &Address, winner: &Option<Address>) -> &'a mut u32 {
+    match winner {
+        None => &mut stats.draws,
+        Some(w) if w == player => &mut stats.wins,
+        Some(_) => &mut stats.losses,
+    }
+}
+
+
+pub fn leaderboard(env: &Env) -> Vec<LeaderboardEntry> {
+    env.storage().persistent().get(&DataKey::Leaderboard).unwrap_or(Vec::new(env))
+}
+
+// Re-ranks `player` from their current stats: most wins first, then most runs, then whoever got there first.
+// Players without a win drop off.
+fn refresh_leaderboard(env: &Env, player: &Address) {
+    let mut board = leaderboard(env);
+    let existing = board.iter().position(|e| &e.player == player);
+    if let Some(i) = existing { board.remove(i as u32); }
+    let stats = player_stats(env, player);
+    let rank = (stats.wins, stats.total_runs_scored);
+    let at = board.iter().position(|e| (e.wins, e.runs) < rank).map_or(board.len(), |i| i as u32);
+    let placed = stats.wins > 0 && at < LEADERBOARD_SIZE;
+    if placed { board.insert(at, LeaderboardEntry { player: player.clone(), wins: stats.wins, runs: stats.total_runs_scored }); }
+    if existing.is_none() && !placed { return; }
+    if board.len() > LEADERBOARD_SIZE { board.pop_back(); }
+    env.storage().persistent().set(&DataKey::Leaderboard, &board);
+    env.storage().persistent().extend_ttl(&DataKey::Leaderboard, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+fn sorted_pair<'a>(a: &'a Address, b: &'a Address) -> (&'a Address, &'a Address) {
+    if a <= b { (a, b) } else { (b, a) }
+}
+
+pub fn head_to_head(env: &Env, a: &Address, b: &Address) -> HeadToHead {
+    let (a, b) = sorted_pair(a, b);
+    env.storage().persistent().get(&DataKey::HeadToHead(a.clone(), b.clone())).unwrap_or_default()
+}
+
+// Kept only while neither player has opted out; `update` is given the record's lower address.
+fn update_head_to_head(env: &Env, game: &Game, update: impl FnOnce(&mut HeadToHead, &Address)) {
+    let persistent = env.storage().persistent();
+    if [&game.player1, &game.player2].iter().any(|p| persistent.has(&DataKey::StatsOptOut((*p).clone()))) { return; }
+    let (a, b) = sorted_pair(&game.player1, &game.player2);
+    let key = DataKey::HeadToHead(a.clone(), b.clone());
+    if !persistent.has(&key) {
+        add_opponent(env, a, b);
+        add_opponent(env, b, a);
+    }
+    let mut record = head_to_head(env, a, b);
+    update(&mut record, a);
+    persistent.set(&key, &record);
+    persistent.extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+fn opponents(env: &Env, player: &Address) -> Vec<Address> {
+    env.storage().persistent().get(&DataKey::Opponents(player.clone())).unwrap_or(Vec::new(env))
+}
+
+fn add_opponent(env: &Env, player: &Address, opponent: &Address) {
+    let mut list = opponents(env, player);
+    list.push_back(opponent.clone());
+    let key = DataKey::Opponents(player.clone());
+    env.storage().persistent().set(&key, &list);
+    env.storage().persistent().extend_ttl(&key, PERSISTENT_TTL_LEDGERS, PERSISTENT_TTL_LEDGERS);
+}
+
+fn head_to_head_count<'a>(record: &'a mut HeadToHead, p_a: &Address, winner: &Option<Address>) -> &'a mut u32 {
+    match winner {
+        None => &mut record.draws,
+        Some(w) if w == p_a => &mut record.p_a_wins,
+        Some(_) => &mut record.p_b_wins,
+    }
+}
+
+// Hooks called by the game. Practice games are never recorded, and nothing is while the operator has
+// switched the feature off; existing records stay readable.
+
+fn recording(env: &Env) -> bool {
+    enabled_features(env) & features::STATS != 0
+}
+
+pub fn record_wicket(env: &Env, game: &Game, bowler: &Address) {
+    if game.is_practice || !recording(env) { return; }
+    update_stats(env, bowler, |stats| stats.total_wickets_taken = stats.total_wickets_taken.saturating_add(1));
+}
+
+pub fn record_finished(env: &Env, session_id: u32, game: &Game) {
+    if !recording(env) { return; }
+    for (player, runs) in [(&game.player1, game.p1_score), (&game.player2, game.p2_score)] {
+        update_stats(env, player, |stats| {
+            stats.games_played = stats.games_played.saturating_add(1);
+            let count = outcome_count(stats, player, &game.winner);
+            *count = count.saturating_add(1);
+            stats.total_runs_scored = stats.total_runs_scored.saturating_add(runs);
+        });
+    }
+    update_head_to_head(env, game, |record, p_a| {
+        let count = head_to_head_count(record, p_a, &game.winner);
+        *count = count.saturating_add(1);
+        record.last_session = Some(session_id);
+    });
+    refresh_leaderboard(env, &game.player1);
+    refresh_leaderboard(env, &game.player2);
+}
+
+// Moves a finished game's result from `previous` to its current winner.
+pub fn record_disputed(env: &Env, game: &Game, previous: &Option<Address>) {
+    if !recording(env) { return; }
+    for player in [&game.player1, &game.player2] {
+        update_stats(env, player, |stats| {
+            let count = outcome_count(stats, player, previous);
+            *count = count.saturating_sub(1);
+            let count = outcome_count(stats, player, &game.winner);
+            *count = count.saturating_add(1);
+        });
+    }
+    update_head_to_head(env, game, |record, p_a| {
+        let count = head_to_head_count(record, p_a, previous);
+        *count = count.saturating_sub(1);
+        let count = head_to_head_count(record, p_a, &game.winner);
+        *count = count.saturating_add(1);
+    });
+    refresh_leaderboard(env, &game.player1);
+    refresh_leaderboard(env, &game.player2);
+}
+
+// Drops the player's stats and every head-to-head record they are in; opponents keep their own stats.
+pub fn forget(env: &Env, player: &Address) {
+    let persistent = env.storage().persistent();
+    persistent.remove(&DataKey::Stats(player.clone()));
+    for opponent in opponents(env, player).iter() {
+        let (a, b) = sorted_pair(player, &opponent);
+        persistent.remove(&DataKey::HeadToHead(a.clone(), b.clone()));
+        let mut theirs = opponents(env, &opponent);
+        if let Some(i) = theirs.first_index_of(player) { theirs.remove(i); }
+        let key = DataKey::Opponents(opponent.clone());
+        if theirs.is_empty() { persistent.remove(&key); } else { persistent.set(&key, &theirs); }
+    }
+    persistent.remove(&DataKey::Opponents(player.clone()));
+    refresh_leaderboard(env, player);
+}
@@ -0,0 +1,212 @@
+//! Reference encoding for commitments and proof blobs.
+//!
+//! These functions are the canonical definition of the bytes a client must
+//! produce. They are pure: the same [`Preimage`] always yields the same
+//! commitment and blob, whatever the environment state. The commitment
+//! section of the [`crate::conformance`] vectors is generated from them.
+//!
+//! Layouts (all integers big-endian):
+//!
+//! * [`Scheme::LegacyBlob`] commitment: `keccak256(number u32 || salt 32)`.
+//!   The session, ball index and player are not bound by this scheme.
+//! * Reveal context ([`make_context`]): `keccak256(session_id u32 || innings u32 || ball_index u32`
+//!   `|| keccak256(player strkey))`; see [`crate::reveal_context`].
+//! * [`Scheme::SaltedHash`] commitment, opened by `reveal_with_salt`:
+//!   `keccak256(number u32 || salt 32 || context 32)`; see [`crate::salted_commitment`].
+//! * Proof blob: `[vk_version u32][num_inputs u32 = 2][commitment 32]`
+//!   `[number field 32 = 28 zero bytes || number u32][proof 64]`.
+//! * Groth16 proof blob ([`groth16_proof_blob`]), for keys set with `set_verification_key`:
+//!   `[vk_version u32][num_inputs u32 = 3][commitment 32][number field 32][context 32]`,
+//!   then `[a G1 96][b G2 192][c G1 96]`. The key layout is
+//!   `[alpha G1][beta G2][gamma G2][delta G2][ic0 G1]..[ic3 G1]`.
+//! * Builds without the `zk` feature reveal with the bare 32-byte salt
+//!   instead; [`make_reveal`] picks whichever the build expects.
+//! * Settlement transcript ([`make_transcript`]): `[p1 toss u32][p2 toss u32]`
+//!   `[toss winner bats u32 = 0|1][ball count u32]`, then per ball
+//!   `[index u32 from 0][p1 number u32][p2 number u32]`, then
+//!   `[claimed winner u32 = 1|2, 0 for a draw]`. Both players sign over its keccak256.
+//! * Merkle tree ([`merkle_tree`]) for `commit_merkle`: leaves `keccak256(number u32 || salt 32 || index u32)`,
+//!   padded with zero leaves to a power of two; each parent is `keccak256(left 32 || right 32)`.
+
+use soroban_sdk::crypto::bls12_381::{Fr, G1Affine, G2Affine};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Vec, U256};
+
+/// Commitment scheme a fixture is produced for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Scheme {
+    /// Plain hash commitment revealed through the proof blob.
+    LegacyBlob,
+    /// Hash commitment bound to the session, round and player, revealed with the bare salt.
+    SaltedHash,
+}
+
+/// Everything a player knows when committing to a number.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Preimage {
+    pub number: u32,
+    pub salt: [u8; 32],
+    pub session_id: u32,
+    pub innings: u32,
+    /// Rounds played so far in the game, toss included.
+    pub ball_index: u32,
+    pub player: Address,
+}
+
+/// Expand a small seed into a 32-byte salt (seed in the last four bytes).
+pub fn salt(seed: u32) -> [u8; 32] {
+    let mut salt = [0u8; 32];
+    salt[28..].copy_from_slice(&seed.to_be_bytes());
+    salt
+}
+
+pub fn make_commitment(env: &Env, scheme: Scheme, preimage: &Preimage) -> BytesN<32> {
+    match scheme {
+        Scheme::LegacyBlob => {
+            let mut bytes = Bytes::from_array(env, &preimage.number.to_be_bytes());
+            bytes.append(&Bytes::from_array(env, &preimage.salt));
+            env.crypto().keccak256(&bytes).into()
+        }
+        Scheme::SaltedHash => crate::salted_commitment(
+            env,
+            &make_context(env, preimage),
+            preimage.number,
+            &BytesN::from_array(env, &preimage.salt),
+        ),
+    }
+}
+
+/// The round `preimage` is committed for, as bound by the salted scheme and Groth16 proofs.
+pub fn make_context(env: &Env, preimage: &Preimage) -> BytesN<32> {
+    crate::reveal_context(env, preimage.session_id, preimage.innings, preimage.ball_index, &preimage.player)
+}
+
+/// Proof blob revealing `preimage` against verifying key `vk_version`.
+pub fn make_proof_blob(env: &Env, scheme: Scheme, vk_version: u32, preimage: &Preimage) -> Bytes {
+    proof_blob_for_commitment(env, vk_version, &make_commitment(env, scheme, preimage), preimage.number)
+}
+
+/// The `proof_blob` argument `reveal_number` expects from this build.
+pub fn make_reveal(env: &Env, scheme: Scheme, vk_version: u32, preimage: &Preimage) -> Bytes {
+    if cfg!(feature = "zk") {
+        make_proof_blob(env, scheme, vk_version, preimage)
+    } else {
+        Bytes::from_array(env, &preimage.salt)
+    }
+}
+
+/// Proof blob for an already computed commitment, for tests that pair a blob with a foreign commitment.
+pub fn proof_blob_for_commitment(env: &Env, vk_version: u32, commitment: &BytesN<32>, number: u32) -> Bytes {
+    let mut blob = Bytes::from_array(env, &vk_version.to_be_bytes());
+    blob.append(&Bytes::from_array(env, &2u32.to_be_bytes()));
+    blob.append(commitment.as_bytes());
+    blob.append(&Bytes::from_array(env, &[0u8; 28]));
+    blob.append(&Bytes::from_array(env, &number.to_be_bytes()));
+    blob.append(&Bytes::from_array(env, &[0u8; 64]));
+    blob
+}
+
+// Trapdoor of the fixture key: alpha, beta, gamma, delta, then ic0..ic3, all multiples of fixed base points.
+const GROTH16_TRAPDOOR: [u32; 8] = [11, 13, 17, 19, 23, 29, 31, 43];
+// Blinding scalars for the a and b proof points.
+const GROTH16_PROOF_RS: (u32, u32) = (37, 41);
+
+fn fr(env: &Env, value: u32) -> Fr {
+    Fr::from_u256(U256::from_u32(env, value))
+}
+
+fn groth16_bases(env: &Env) -> (G1Affine, G2Affine) {
+    let bls = env.crypto().bls12_381();
+    let msg = Bytes::from_slice(env, b"hand-cricket fixture");
+    (
+        bls.hash_to_g1(&msg, &Bytes::from_slice(env, b"HAND-CRICKET-FIXTURE-G1")),
+        bls.hash_to_g2(&msg, &Bytes::from_slice(env, b"HAND-CRICKET-FIXTURE-G2")),
+    )
+}
+
+/// Groth16 verifying key for `set_verification_key`, built from a known trapdoor.
+pub fn groth16_verifying_key(env: &Env) -> Bytes {
+    let bls = env.crypto().bls12_381();
+    let (g, h) = groth16_bases(env);
+    let [alpha, beta, gamma, delta, ic0, ic1, ic2, ic3] = GROTH16_TRAPDOOR.map(|k| fr(env, k));
+    let mut key = Bytes::new(env);
+    key.append(&bls.g1_mul(&g, &alpha).to_bytes().into());
+    for scalar in [beta, gamma, delta] {
+        key.append(&bls.g2_mul(&h, &scalar).to_bytes().into());
+    }
+    for scalar in [ic0, ic1, ic2, ic3] {
+        key.append(&bls.g1_mul(&g, &scalar).to_bytes().into());
+    }
+    key
+}
+
+/// Groth16 proof blob for [`groth16_verifying_key`] registered as `vk_version`.
+///
+/// With the trapdoor known the proof is simulated rather than proven: `c` is
+/// solved from the pairing equation, so it verifies for any public inputs.
+pub fn groth16_proof_blob(env: &Env, vk_version: u32, commitment: &BytesN<32>, number: u32, context: &BytesN<32>) -> Bytes {
+    let bls = env.crypto().bls12_381();
+    let (g, h) = groth16_bases(env);
+    let [alpha, beta, gamma, delta, ic0, ic1, ic2, ic3] = GROTH16_TRAPDOOR.map(|k| fr(env, k));
+    let (r, s) = (fr(env, GROTH16_PROOF_RS.0), fr(env, GROTH16_PROOF_RS.1));
+    // e(a, b) = e(alpha, beta) e(ic, gamma) e(c, delta) in the exponent: r s = alpha beta + ic gamma + c delta.
+    let ic = [
+        bls.fr_mul(&Fr::from_bytes(commitment.clone()), &ic1),
+        bls.fr_mul(&fr(env, number), &ic2),
+        bls.fr_mul(&Fr::from_bytes(context.clone()), &ic3),
+    ]
+    .iter()
+    .fold(ic0, |sum, term| bls.fr_add(&sum, term));
+    let c = bls.fr_mul(&bls.fr_sub(&bls.fr_sub(&bls.fr_mul(&r, &s), &bls.fr_mul(&alpha, &beta)), &bls.fr_mul(&ic, &gamma)), &delta.inv());
+
+    let mut blob = Bytes::from_array(env, &vk_version.to_be_bytes());
+    blob.append(&Bytes::from_array(env, &3u32.to_be_bytes()));
+    blob.append(&proof_blob_for_commitment(env, vk_version, commitment, number).slice(8..72));
+    blob.append(context.as_bytes());
+    blob.append(&bls.g1_mul(&g, &r).to_bytes().into());
+    blob.append(&bls.g2_mul(&h, &s).to_bytes().into());
+    blob.append(&bls.g1_mul(&g, &c).to_bytes().into());
+    blob
+}
+
+/// Transcript for `settle_transcript`. `claimed_winner` is 1 for player1, 2 for player2, 0 for a draw.
+pub fn make_transcript(env: &Env, toss: (u32, u32), winner_bats: bool, balls: &[(u32, u32)], claimed_winner: u32) -> Bytes {
+    let mut transcript = Bytes::new(env);
+    let mut push = |value: u32| transcript.append(&Bytes::from_array(env, &value.to_be_bytes()));
+    push(toss.0);
+    push(toss.1);
+    push(winner_bats as u32);
+    push(balls.len() as u32);
+    for (i, (p1, p2)) in balls.iter().enumerate() {
+        push(i as u32);
+        push(*p1);
+        push(*p2);
+    }
+    push(claimed_winner);
+    transcript
+}
+
+/// Root of a `commit_merkle` tree over `(number, salt)` leaves in ball order, and each leaf's `reveal_leaf` path.
+pub fn merkle_tree(env: &Env, leaves: &[(u32, [u8; 32])]) -> (BytesN<32>, Vec<Vec<BytesN<32>>>) {
+    let mut level = Vec::new(env);
+    for (index, (number, salt)) in leaves.iter().enumerate() {
+        level.push_back(crate::merkle_leaf(env, *number, &BytesN::from_array(env, salt), index as u32));
+    }
+    while level.len() < (leaves.len() as u32).next_power_of_two() { level.push_back(BytesN::from_array(env, &[0; 32])); }
+    let mut paths: Vec<Vec<BytesN<32>>> = Vec::new(env);
+    for _ in leaves { paths.push_back(Vec::new(env)); }
+    while level.len() > 1 {
+        for (index, mut path) in paths.iter().enumerate() {
+            let at = (index as u32) >> path.len();
+            path.push_back(level.get_unchecked(at ^ 1));
+            paths.set(index as u32, path);
+        }
+        let mut parents = Vec::new(env);
+        for i in (0..level.len()).step_by(2) {
+            let mut pair = Bytes::from_array(env, &level.get_unchecked(i).to_array());
+            pair.append(level.get_unchecked(i + 1).as_bytes());
+            parents.push_back(env.crypto().keccak256(&pair).into());
+        }
+        level = parents;
+    }
+    (level.get_unchecked(0), paths)
+}
@@ -0,0 +1,414 @@
+//! Test utilities for integrating with hand-cricket.
+//!
+//! Enabled with the `testutils` cargo feature. Provides a recording mock
+//! GameHub and helpers that deploy it together with `HandCricketContract`,
+//! plus a [`MockVerifier`] for `set_verifier`.
+//!
+//! ```ignore
+//! use hand_cricket::testutils::{setup, MockGameHub};
+//!
+//! let t = setup();
+//! t.client.start_game(&1, &t.player1, &t.player2, &100, &100);
+//! assert_eq!(t.hub.get_start_calls().len(), 1);
+//! ```
+//!
+//! For whole-game scenarios see [`script::GameScript`]; for commitment and
+//! proof bytes see [`fixtures`].
+
+pub mod fixtures;
+pub mod script;
+
+use crate::{DataKey, Game, GameV1, HandCricketContract, HandCricketContractClient, InitConfig, StoredGame};
+use soroban_sdk::testutils::{Address as _, Ledger as _, LedgerInfo};
+use soroban_sdk::{
+    contract, contracterror, contractimpl, contracttype, Address, Bytes, BytesN, Env, IntoVal, Val, Vec,
+};
+
+// ============================================================================
+// Mock GameHub
+// ============================================================================
+
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum MockHubError {
+    Rejected = 1,
+}
+
+/// How a mock hub method should fail when called.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FailureMode {
+    None,
+    Panic,
+    Reject,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct StartCall {
+    pub game_id: Address,
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    pub player1_points: i128,
+    pub player2_points: i128,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EndCall {
+    pub session_id: u32,
+    pub player1_won: bool,
+}
+
+/// Scorecard passed to `end_game_detailed`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DetailedEndCall {
+    pub session_id: u32,
+    pub player1_won: bool,
+    pub p1_score: u32,
+    pub p2_score: u32,
+    pub target: u32,
+    pub innings_played: u32,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum MockHubKey {
+    StartCalls,
+    EndCalls,
+    DetailedEndCalls,
+    DrawCalls,
+    CancelCalls,
+    StartFailure,
+    EndFailure,
+}
+
+/// Mock GameHub that records every call so tests can assert on them.
+#[contract]
+pub struct MockGameHub;
+
+#[contractimpl]
+impl MockGameHub {
+    pub fn start_game(
+        env: Env,
+        game_id: Address,
+        session_id: u32,
+        player1: Address,
+        player2: Address,
+        player1_points: i128,
+        player2_points: i128,
+    ) -> Result<(), MockHubError> {
+        check_failure(&env, &MockHubKey::StartFailure)?;
+        let mut calls = Self::get_start_calls(env.clone());
+        calls.push_back(StartCall {
+            game_id,
+            session_id,
+            player1,
+            player2,
+            player1_points,
+            player2_points,
+        });
+        env.storage().instance().set(&MockHubKey::StartCalls, &calls);
+        Ok(())
+    }
+
+    pub fn end_game(env: Env, session_id: u32, player1_won: bool) -> Result<(), MockHubError> {
+        check_failure(&env, &MockHubKey::EndFailure)?;
+        let mut calls = Self::get_end_calls(env.clone());
+        calls.push_back(EndCall { session_id, player1_won });
+        env.storage().instance().set(&MockHubKey::EndCalls, &calls);
+        Ok(())
+    }
+
+    /// Records the scorecard, and the result as an [`EndCall`] too; fails like `end_game` does.
+    pub fn end_game_detailed(
+        env: Env,
+        session_id: u32,
+        player1_won: bool,
+        p1_score: u32,
+        p2_score: u32,
+        target: u32,
+        innings_played: u32,
+    ) -> Result<(), MockHubError> {
+        Self::end_game(env.clone(), session_id, player1_won)?;
+        let mut calls = Self::get_detailed_end_calls(env.clone());
+        calls.push_back(DetailedEndCall { session_id, player1_won, p1_score, p2_score, target, innings_played });
+        env.storage().instance().set(&MockHubKey::DetailedEndCalls, &calls);
+        Ok(())
+    }
+
+    /// Records the session id; fails like `end_game` does.
+    pub fn end_game_draw(env: Env, session_id: u32) -> Result<(), MockHubError> {
+        check_failure(&env, &MockHubKey::EndFailure)?;
+        let mut calls = Self::get_draw_calls(env.clone());
+        calls.push_back(session_id);
+        env.storage().instance().set(&MockHubKey::DrawCalls, &calls);
+        Ok(())
+    }
+
+    /// Records the session id; fails like `end_game` does.
+    pub fn cancel_game(env: Env, session_id: u32) -> Result<(), MockHubError> {
+        check_failure(&env, &MockHubKey::EndFailure)?;
+        let mut calls = Self::get_cancel_calls(env.clone());
+        calls.push_back(session_id);
+        env.storage().instance().set(&MockHubKey::CancelCalls, &calls);
+        Ok(())
+    }
+
+    pub fn add_game(_env: Env, _game_address: Address) {
+        // Whitelisting is not modelled by the mock
+    }
+
+    pub fn get_start_calls(env: Env) -> Vec<StartCall> {
+        env.storage()
+            .instance()
+            .get(&MockHubKey::StartCalls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_end_calls(env: Env) -> Vec<EndCall> {
+        env.storage()
+            .instance()
+            .get(&MockHubKey::EndCalls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_detailed_end_calls(env: Env) -> Vec<DetailedEndCall> {
+        env.storage()
+            .instance()
+            .get(&MockHubKey::DetailedEndCalls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_draw_calls(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&MockHubKey::DrawCalls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn get_cancel_calls(env: Env) -> Vec<u32> {
+        env.storage()
+            .instance()
+            .get(&MockHubKey::CancelCalls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Make subsequent `start_game` calls panic or return an error.
+    pub fn set_start_failure(env: Env, mode: FailureMode) {
+        env.storage().instance().set(&MockHubKey::StartFailure, &mode);
+    }
+
+    /// Make subsequent `end_game`, `end_game_detailed`, `end_game_draw` and `cancel_game` calls panic or return an error.
+    pub fn set_end_failure(env: Env, mode: FailureMode) {
+        env.storage().instance().set(&MockHubKey::EndFailure, &mode);
+    }
+}
+
+fn check_failure(env: &Env, key: &MockHubKey) -> Result<(), MockHubError> {
+    match env.storage().instance().get(key).unwrap_or(FailureMode::None) {
+        FailureMode::None => Ok(()),
+        FailureMode::Panic => panic!("mock hub configured to panic"),
+        FailureMode::Reject => Err(MockHubError::Rejected),
+    }
+}
+
+// ============================================================================
+// Mock Verifier
+// ============================================================================
+
+/// What [`MockVerifier::verify`] answers.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Accept,
+    Reject,
+    Panic,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VerifyCall {
+    pub commitment: BytesN<32>,
+    pub number: u32,
+    pub context: BytesN<32>,
+    pub proof: Bytes,
+}
+
+#[contracttype]
+#[derive(Clone)]
+enum MockVerifierKey {
+    Verdict,
+    Calls,
+}
+
+/// Verifier for `set_verifier` that answers whatever it was last told to and records every call.
+#[contract]
+pub struct MockVerifier;
+
+#[contractimpl]
+impl MockVerifier {
+    pub fn verify(env: Env, commitment: BytesN<32>, number: u32, context: BytesN<32>, proof: Bytes) -> bool {
+        let mut calls = Self::get_verify_calls(env.clone());
+        calls.push_back(VerifyCall { commitment, number, context, proof });
+        env.storage().instance().set(&MockVerifierKey::Calls, &calls);
+        match env.storage().instance().get(&MockVerifierKey::Verdict).unwrap_or(Verdict::Accept) {
+            Verdict::Accept => true,
+            Verdict::Reject => false,
+            Verdict::Panic => panic!("mock verifier configured to panic"),
+        }
+    }
+
+    pub fn get_verify_calls(env: Env) -> Vec<VerifyCall> {
+        env.storage()
+            .instance()
+            .get(&MockVerifierKey::Calls)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Defaults to [`Verdict::Accept`].
+    pub fn set_verdict(env: Env, verdict: Verdict) {
+        env.storage().instance().set(&MockVerifierKey::Verdict, &verdict);
+    }
+}
+
+// ============================================================================
+// Setup Helpers
+// ============================================================================
+
+/// Everything a test needs to drive a game.
+pub struct Setup {
+    pub env: Env,
+    pub client: HandCricketContractClient<'static>,
+    pub hub: MockGameHubClient<'static>,
+    pub admin: Address,
+    pub player1: Address,
+    pub player2: Address,
+}
+
+/// Default verifying key version registered by [`setup`].
+pub const DEFAULT_VK_VERSION: u32 = 1;
+
+/// Configure the ledger the way the crate's own tests expect.
+pub fn set_default_ledger(env: &Env) {
+    env.ledger().set(LedgerInfo {
+        timestamp: 1441065600,
+        protocol_version: 25,
+        sequence_number: 100,
+        network_id: Default::default(),
+        base_reserve: 10,
+        min_temp_entry_ttl: u32::MAX / 2,
+        min_persistent_entry_ttl: u32::MAX / 2,
+        max_entry_ttl: u32::MAX / 2,
+    });
+}
+
+/// Deploy a mock hub and a hand-cricket contract wired to it, with
+/// [`DEFAULT_VK_VERSION`] registered (in `zk` builds) so games can start immediately.
+pub fn deploy(
+    env: &Env,
+    admin: &Address,
+) -> (HandCricketContractClient<'static>, MockGameHubClient<'static>) {
+    let hub_addr = env.register(MockGameHub, ());
+    let hub = MockGameHubClient::new(env, &hub_addr);
+    let contract_id = env.register(HandCricketContract, (admin, Some(hub_addr.clone()), None::<InitConfig>));
+    let client = HandCricketContractClient::new(env, &contract_id);
+    hub.add_game(&contract_id);
+    #[cfg(feature = "zk")]
+    client.add_verifying_key(&DEFAULT_VK_VERSION, &Bytes::from_array(env, &[1u8; 32]));
+    (client, hub)
+}
+
+/// Fresh env with all auths mocked, a deployed contract/hub pair, and two players.
+pub fn setup() -> Setup {
+    setup_in(Env::default())
+}
+
+/// Like [`setup`] but on a caller-provided env, e.g. one built with
+/// `Env::new_with_config` to skip writing a test snapshot on drop.
+pub fn setup_in(env: Env) -> Setup {
+    env.mock_all_auths();
+    set_default_ledger(&env);
+
+    let admin = Address::generate(&env);
+    let (client, hub) = deploy(&env, &admin);
+    let player1 = Address::generate(&env);
+    let player2 = Address::generate(&env);
+
+    Setup {
+        env,
+        client,
+        hub,
+        admin,
+        player1,
+        player2,
+    }
+}
+
+// ============================================================================
+// Ledger Time Travel
+// ============================================================================
+//
+// Deadline tests: start the game, advance past the window with
+// `advance_ledgers`, or back-date a single game with `set_game_phase_started`
+// when other games must stay inside it.
+
+/// Move the ledger sequence forward by `n`, leaving every other field alone.
+pub fn advance_ledgers(env: &Env, n: u32) {
+    env.ledger().with_mut(|li| li.sequence_number += n);
+}
+
+/// Move the ledger timestamp forward by `seconds`, leaving every other field alone.
+pub fn advance_time(env: &Env, seconds: u64) {
+    env.ledger().with_mut(|li| li.timestamp += seconds);
+}
+
+/// Back-date (or forward-date) the ledger a game's current phase began at.
+pub fn set_game_phase_started(env: &Env, contract_id: &Address, session_id: u32, ledger: u32) {
+    let mut game = read_raw_game(env, contract_id, session_id).expect("game not found");
+    game.phase_started_ledger = ledger;
+    write_raw_game(env, contract_id, session_id, &game);
+}
+
+// ============================================================================
+// Raw State Access
+// ============================================================================
+
+/// Read the stored `Game` directly from contract storage, bypassing entrypoints.
+/// Older layouts are upgraded in memory, as the contract would.
+pub fn read_raw_game(env: &Env, contract_id: &Address, session_id: u32) -> Option<Game> {
+    read_raw_value(env, contract_id, session_id).map(|raw| crate::decode_game(env, &raw).0)
+}
+
+/// The game entry exactly as stored.
+pub fn read_raw_value(env: &Env, contract_id: &Address, session_id: u32) -> Option<Val> {
+    env.as_contract(contract_id, || env.storage().temporary().get(&DataKey::Game(session_id)))
+}
+
+/// Overwrite the stored `Game` in the current layout, e.g. to plant a corrupted state.
+pub fn write_raw_game(env: &Env, contract_id: &Address, session_id: u32, game: &Game) {
+    write_raw_value(env, contract_id, session_id, &StoredGame::V2(game.clone()));
+}
+
+/// `game` in the V1 layout, for planting games written by older code. Everything added since is dropped.
+pub fn as_v1_layout(game: &Game) -> GameV1 {
+    let game = game.clone();
+    GameV1 {
+        player1: game.player1, player2: game.player2,
+        player1_points: game.player1_points, player2_points: game.player2_points,
+        player1_is_odd: game.player1_is_odd, toss_winner: game.toss_winner, batter: game.batter,
+        p1_commitment: game.p1_commitment, p2_commitment: game.p2_commitment,
+        p1_number: game.p1_number, p2_number: game.p2_number,
+        p1_score: game.p1_score, p2_score: game.p2_score, innings: game.innings, target: game.target,
+        phase: game.phase, winner: game.winner,
+    }
+}
+
+/// Overwrite a game entry with any value, e.g. a game in an older layout.
+pub fn write_raw_value<V: IntoVal<Env, Val>>(env: &Env, contract_id: &Address, session_id: u32, value: &V) {
+    let value: Val = value.into_val(env);
+    env.as_contract(contract_id, || env.storage().temporary().set(&DataKey::Game(session_id), &value));
+}
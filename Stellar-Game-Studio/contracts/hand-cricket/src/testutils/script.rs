@@ -0,0 +1,188 @@
+//! Scripted full-game harness.
+//!
+//! A [`GameScript`] drives a started game through a list of [`Step`]s,
+//! generating commitments and proof blobs for every round and asserting the
+//! phase the contract lands in after each step against a small reference
+//! model of the rules.
+//!
+//! ```ignore
+//! let t = setup();
+//! let script = GameScript::start(&t, 1);
+//! script.run(&[TossWonBy(P1), ChooseBat(P1), Ball(6, 2), Ball(4, 4), Ball(1, 1)]);
+//! ```
+
+use super::fixtures::{self, Preimage, Scheme};
+use super::Setup;
+use crate::{Game, HandCricketContractClient, Phase};
+use core::cell::Cell;
+use soroban_sdk::{Address, Env};
+
+/// Which side of the game a step refers to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Who {
+    P1,
+    P2,
+}
+
+/// One scripted action.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step {
+    /// Toss round with player1's and player2's numbers.
+    Toss(u32, u32),
+    /// Toss round with numbers picked so the given side wins it.
+    TossWonBy(Who),
+    /// The toss winner elects to bat.
+    ChooseBat(Who),
+    /// The toss winner elects to bowl.
+    ChooseBowl(Who),
+    /// One ball with player1's and player2's numbers.
+    Ball(u32, u32),
+}
+
+pub struct GameScript<'a> {
+    pub env: &'a Env,
+    pub client: &'a HandCricketContractClient<'static>,
+    pub session_id: u32,
+    pub player1: Address,
+    pub player2: Address,
+    rounds: Cell<u32>,
+}
+
+impl<'a> GameScript<'a> {
+    /// Start a game between `t.player1` and `t.player2` with equal stakes and wrap it.
+    pub fn start(t: &'a Setup, session_id: u32) -> Self {
+        t.client
+            .start_game(&session_id, &t.player1, &t.player2, &100_0000000, &100_0000000);
+        Self::attach(t, session_id)
+    }
+
+    /// Start a game between `player1` and `player2` with equal stakes and wrap it.
+    pub fn start_between(t: &'a Setup, session_id: u32, player1: &Address, player2: &Address) -> Self {
+        t.client.start_game(&session_id, player1, player2, &100_0000000, &100_0000000);
+        GameScript { player1: player1.clone(), player2: player2.clone(), ..Self::attach(t, session_id) }
+    }
+
+    /// Wrap a game that has already been started.
+    pub fn attach(t: &'a Setup, session_id: u32) -> Self {
+        GameScript {
+            env: &t.env,
+            client: &t.client,
+            session_id,
+            player1: t.player1.clone(),
+            player2: t.player2.clone(),
+            rounds: Cell::new(0),
+        }
+    }
+
+    pub fn address(&self, who: Who) -> &Address {
+        match who {
+            Who::P1 => &self.player1,
+            Who::P2 => &self.player2,
+        }
+    }
+
+    pub fn game(&self) -> Game {
+        self.client.get_game(&self.session_id)
+    }
+
+    /// Run every step in order, asserting the resulting phase after each.
+    pub fn run(&self, steps: &[Step]) -> Game {
+        for (i, step) in steps.iter().enumerate() {
+            let before = self.game();
+            let expected = expected_phase(&before, self, *step);
+            self.step(*step);
+            let after = self.game();
+            assert_eq!(
+                after.phase, expected,
+                "step {} ({:?}) left the game in {:?}, expected {:?}",
+                i, step, after.phase, expected
+            );
+        }
+        self.game()
+    }
+
+    /// Execute a single step without checking the resulting phase.
+    pub fn step(&self, step: Step) {
+        match step {
+            Step::Toss(n1, n2) => self.round(n1, n2),
+            Step::TossWonBy(who) => {
+                let (n1, n2) = toss_numbers_for(&self.game(), who);
+                self.round(n1, n2);
+            }
+            Step::ChooseBat(who) => {
+                self.client.choose_role(&self.session_id, self.address(who), &true);
+            }
+            Step::ChooseBowl(who) => {
+                self.client.choose_role(&self.session_id, self.address(who), &false);
+            }
+            Step::Ball(n1, n2) => self.round(n1, n2),
+        }
+    }
+
+    /// Commit and reveal one round for both players.
+    pub fn round(&self, p1_number: u32, p2_number: u32) {
+        let ball_index = self.rounds.get();
+        self.rounds.set(ball_index + 1);
+        let p1 = self.preimage(&self.player1, p1_number, ball_index);
+        let p2 = self.preimage(&self.player2, p2_number, ball_index);
+        let scheme = Scheme::LegacyBlob;
+        self.client.commit_number(&self.session_id, &self.player1, &fixtures::make_commitment(self.env, scheme, &p1));
+        self.client.commit_number(&self.session_id, &self.player2, &fixtures::make_commitment(self.env, scheme, &p2));
+        let vk_version = self.game().vk_version.unwrap_or(super::DEFAULT_VK_VERSION);
+        self.client.reveal_number(
+            &self.session_id,
+            &self.player1,
+            &p1_number,
+            &fixtures::make_reveal(self.env, scheme, vk_version, &p1),
+        );
+        self.client.reveal_number(
+            &self.session_id,
+            &self.player2,
+            &p2_number,
+            &fixtures::make_reveal(self.env, scheme, vk_version, &p2),
+        );
+    }
+
+    // Salts differ per player and per round so no two commitments in a game collide.
+    fn preimage(&self, player: &Address, number: u32, ball_index: u32) -> Preimage {
+        let seat = if *player == self.player1 { 1 } else { 2 };
+        Preimage {
+            number,
+            salt: fixtures::salt(ball_index * 2 + seat),
+            session_id: self.session_id,
+            innings: self.game().innings,
+            ball_index,
+            player: player.clone(),
+        }
+    }
+}
+
+/// Toss numbers that make `who` win given the game's odd/even assignment.
+pub fn toss_numbers_for(game: &Game, who: Who) -> (u32, u32) {
+    let want_odd_sum = (who == Who::P1) == game.player1_is_odd;
+    if want_odd_sum { (1, 2) } else { (1, 1) }
+}
+
+// Reference model of the phase a step should land in.
+fn expected_phase(game: &Game, script: &GameScript, step: Step) -> Phase {
+    match step {
+        Step::Toss(..) | Step::TossWonBy(_) => Phase::BatBowlChoice,
+        Step::ChooseBat(_) | Step::ChooseBowl(_) => Phase::BallCommit,
+        Step::Ball(n1, n2) => {
+            let out = n1 == n2;
+            let p1_batting = game.batter.as_ref() == Some(&script.player1);
+            let runs = if out { 0 } else if p1_batting { n1 } else { n2 };
+            if game.innings > 2 {
+                let (bat, bowl) = if p1_batting { (game.p1_super_score, game.p2_super_score) } else { (game.p2_super_score, game.p1_super_score) };
+                let half_over = out || game.balls_bowled + 1 >= game.super_over_balls;
+                let decided = bat + runs > bowl || (half_over && bat + runs < bowl);
+                return if game.innings == 4 && decided { Phase::Finished } else { Phase::SuperOver };
+            }
+            let score = runs + if p1_batting { game.p1_score } else { game.p2_score };
+            let last_ball = game.max_balls_per_innings > 0 && game.balls_bowled + 1 >= game.max_balls_per_innings;
+            if game.innings == 1 || !(out || last_ball || score >= game.target) { return Phase::BallCommit; }
+            let tied = score + 1 == game.target;
+            if tied && script.client.get_super_over_balls() > 0 { Phase::SuperOver } else { Phase::Finished }
+        }
+    }
+}
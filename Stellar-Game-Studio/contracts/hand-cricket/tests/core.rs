@@ -0,0 +1,166 @@
+// Core game suite that must pass under every feature set; see
+// tests/feature_matrix.sh for the combinations CI runs it under.
+
+use hand_cricket::features;
+use hand_cricket::testutils::fixtures::{self, Preimage, Scheme};
+use hand_cricket::testutils::script::{GameScript, Step, Step::*, Who::*};
+use hand_cricket::testutils::{advance_ledgers, setup, FailureMode, Setup};
+use hand_cricket::{Error, Phase};
+use soroban_sdk::testutils::Address as _;
+use soroban_sdk::{symbol_short, vec, Address, BytesN};
+
+const P1_WINS: [Step; 5] = [TossWonBy(P1), ChooseBat(P1), Ball(4, 1), Ball(2, 2), Ball(3, 3)];
+
+/// Salted commitment for round `ball_index` of `session_id` in the first innings, and its salt.
+fn salted(t: &Setup, session_id: u32, ball_index: u32, player: &Address, number: u32, seed: u32) -> (BytesN<32>, BytesN<32>) {
+    let p = Preimage { number, salt: fixtures::salt(seed), session_id, innings: 1, ball_index, player: player.clone() };
+    (fixtures::make_commitment(&t.env, Scheme::SaltedHash, &p), BytesN::from_array(&t.env, &p.salt))
+}
+
+#[test]
+fn test_full_game_settles_with_hub() {
+    let t = setup();
+    let game = GameScript::start(&t, 1).run(&[TossWonBy(P1), ChooseBat(P1), Ball(6, 2), Ball(3, 3), Ball(1, 1)]);
+    assert_eq!(game.phase, Phase::Finished);
+    assert_eq!(game.winner, Some(t.player1.clone()));
+    let end = t.hub.get_end_calls().get(0).unwrap();
+    assert_eq!((end.session_id, end.player1_won), (1, true));
+}
+
+#[test]
+fn test_bowling_first_and_chasing_down_the_target() {
+    let t = setup();
+    // Player2 sets 6 and player1 chases it down with 9
+    let game = GameScript::start(&t, 1).run(&[TossWonBy(P1), ChooseBowl(P1), Ball(1, 5), Ball(3, 3), Ball(4, 1), Ball(5, 2)]);
+    assert_eq!((game.p1_score, game.p2_score, game.target), (9, 5, 6));
+    assert_eq!(game.winner, Some(t.player1.clone()));
+    assert!(game.result_reported);
+}
+
+#[test]
+fn test_tied_chase_is_drawn_without_a_super_over() {
+    let t = setup();
+    t.client.set_super_over_balls(&0);
+    let game = GameScript::start(&t, 1).run(&[TossWonBy(P1), ChooseBat(P1), Ball(2, 1), Ball(3, 3), Ball(1, 2), Ball(4, 4)]);
+    assert_eq!((game.phase, game.winner), (Phase::Finished, None));
+    assert_eq!(t.hub.get_draw_calls(), vec![&t.env, 1]);
+    assert!(t.hub.get_end_calls().is_empty());
+}
+
+#[test]
+fn test_wrong_reveal_is_rejected() {
+    let t = setup();
+    GameScript::start(&t, 2);
+    t.client.commit_number(&2, &t.player1, &BytesN::from_array(&t.env, &[7u8; 32]));
+    t.client.commit_number(&2, &t.player2, &BytesN::from_array(&t.env, &[8u8; 32]));
+    // Well formed for this build, but it doesn't open either commitment
+    let preimage = Preimage { number: 3, salt: fixtures::salt(0), session_id: 2, innings: 1, ball_index: 0, player: t.player1.clone() };
+    let bogus = fixtures::make_reveal(&t.env, Scheme::LegacyBlob, 1, &preimage);
+    let result = t.client.try_reveal_number(&2, &t.player1, &3, &bogus);
+    assert_eq!(result, Err(Ok(Error::ProofInvalid)));
+}
+
+#[test]
+fn test_salted_reveals_play_in_every_build() {
+    let t = setup();
+    t.client.start_game(&3, &t.player1, &t.player2, &100, &100);
+    let (c1, s1) = salted(&t, 3, 0, &t.player1, 3, 1);
+    let (c2, s2) = salted(&t, 3, 0, &t.player2, 11, 2);
+    t.client.commit_number(&3, &t.player1, &c1);
+    t.client.commit_number(&3, &t.player2, &c2);
+    let (_, wrong_salt) = salted(&t, 3, 0, &t.player1, 3, 9);
+    assert_eq!(t.client.try_reveal_with_salt(&3, &t.player1, &3, &wrong_salt), Err(Ok(Error::ProofInvalid)));
+    t.client.reveal_with_salt(&3, &t.player1, &3, &s1);
+    // The commitment opens, but the number is off the board
+    assert_eq!(t.client.try_reveal_with_salt(&3, &t.player2, &11, &s2), Err(Ok(Error::NumberOutOfRange)));
+}
+
+#[test]
+fn test_start_rejects_self_play_and_taken_sessions() {
+    let t = setup();
+    assert_eq!(t.client.try_start_game(&4, &t.player1, &t.player1, &100, &100), Err(Ok(Error::SelfPlay)));
+    t.client.start_game(&4, &t.player1, &t.player2, &100, &100);
+    let (a, b) = (Address::generate(&t.env), Address::generate(&t.env));
+    assert_eq!(t.client.try_start_game(&4, &a, &b, &1, &1), Err(Ok(Error::SessionExists)));
+    assert_eq!(t.hub.get_start_calls().len(), 1);
+}
+
+#[test]
+fn test_cancel_before_the_toss_deletes_the_game() {
+    let t = setup();
+    t.client.start_game(&5, &t.player1, &t.player2, &100, &100);
+    t.client.cancel_game(&5);
+    assert_eq!(t.hub.get_cancel_calls(), vec![&t.env, 5]);
+    assert_eq!(t.client.try_get_game(&5), Err(Ok(Error::GameNotFound)));
+    assert_eq!(t.client.active_session_count(), 0);
+}
+
+#[test]
+fn test_concede_hands_the_opponent_the_win() {
+    let t = setup();
+    GameScript::start(&t, 6).run(&[TossWonBy(P1), ChooseBat(P1), Ball(6, 1)]);
+    let view = t.client.concede(&6, &t.player2);
+    assert_eq!((view.phase, view.winner), (Phase::Finished, Some(t.player1.clone())));
+    assert_eq!(t.client.try_concede(&6, &t.player1), Err(Ok(Error::GameAlreadyEnded)));
+}
+
+#[test]
+fn test_claim_timeout_against_a_stalling_opponent() {
+    let t = setup();
+    t.client.start_game(&7, &t.player1, &t.player2, &100, &100);
+    let (c1, _) = salted(&t, 7, 0, &t.player1, 3, 1);
+    t.client.commit_number(&7, &t.player1, &c1);
+    assert_eq!(t.client.try_claim_timeout(&7, &t.player1), Err(Ok(Error::TimeoutNotReached)));
+    assert_eq!(t.client.try_claim_timeout(&7, &t.player2), Err(Ok(Error::NotOpponentsTurn)));
+
+    advance_ledgers(&t.env, t.client.get_timeout_ledgers());
+    let view = t.client.claim_timeout(&7, &t.player1);
+    assert_eq!((view.phase, view.winner), (Phase::Finished, Some(t.player1.clone())));
+    let end = t.hub.get_end_calls().get(0).unwrap();
+    assert_eq!((end.session_id, end.player1_won), (7, true));
+}
+
+#[test]
+fn test_dispute_window_holds_the_result_until_finalize() {
+    let t = setup();
+    t.client.set_dispute_window(&10);
+    GameScript::start(&t, 8).run(&P1_WINS);
+    assert!(t.hub.get_end_calls().is_empty());
+    assert_eq!(t.client.try_finalize(&8), Err(Ok(Error::SettlementPending)));
+
+    advance_ledgers(&t.env, 10);
+    t.client.finalize(&8);
+    let end = t.hub.get_end_calls().get(0).unwrap();
+    assert_eq!((end.session_id, end.player1_won), (8, true));
+    assert!(t.client.get_game(&8).result_reported);
+}
+
+#[test]
+fn test_failed_hub_report_can_be_retried() {
+    let t = setup();
+    t.hub.set_end_failure(&FailureMode::Panic);
+    let game = GameScript::start(&t, 9).run(&P1_WINS);
+    assert_eq!(game.phase, Phase::Finished);
+    assert!(!game.result_reported);
+    assert_eq!(t.client.try_report_result(&9), Err(Ok(Error::HubReportFailed)));
+
+    t.hub.set_end_failure(&FailureMode::None);
+    t.client.report_result(&9);
+    assert!(t.client.get_game(&9).result_reported);
+    assert_eq!(t.hub.get_end_calls().len(), 1);
+}
+
+#[test]
+fn test_features_follow_cargo_features() {
+    let t = setup();
+    let features = t.client.get_features();
+    assert_eq!(features & features::PROOF_MODE != 0, cfg!(feature = "zk"));
+    assert_eq!(features & features::VK_VERSIONING != 0, cfg!(feature = "zk"));
+    assert_eq!(t.client.supports(&symbol_short!("proof")), cfg!(feature = "zk"));
+    assert!(t.client.supports(&symbol_short!("debug")));
+    assert!(t.client.supports(&symbol_short!("timeouts")));
+    assert!(t.client.supports(&symbol_short!("practice")));
+    assert_eq!(t.client.supports(&symbol_short!("stats")), cfg!(feature = "stats"));
+    assert_eq!(t.client.supports(&symbol_short!("tourney")), cfg!(feature = "tournaments"));
+    assert_eq!(t.client.supports(&symbol_short!("escrow")), cfg!(feature = "escrow"));
+}
@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+# Build and test hand-cricket under each supported feature set.
+#
+#     contracts/hand-cricket/tests/feature_matrix.sh
+set -euo pipefail
+cd "$(dirname "$0")/.."
+
+sets=(
+  "--no-default-features"
+  ""
+  "--all-features"
+)
+
+for set in "${sets[@]}"; do
+  echo "==> cargo build ${set:-(default)}"
+  cargo build $set
+  echo "==> cargo test ${set:-(default)} --features testutils"
+  cargo test $set --features testutils
+done
@@ -0,0 +1,18 @@
+// Integration test showing how a downstream crate can test against
+// hand-cricket with the bundled mock GameHub (requires `--features testutils`).
+
+use hand_cricket::testutils::{setup, MockGameHub};
+use hand_cricket::Phase;
+
+#[test]
+fn test_external_crate_can_drive_a_game() {
+    let t = setup();
+    t.client.start_game(&7, &t.player1, &t.player2, &100, &50);
+
+    assert_eq!(t.client.get_game(&7).phase, Phase::TossCommit);
+    let start = t.hub.get_start_calls().get(0).unwrap();
+    assert_eq!((start.session_id, start.player2_points), (7, 50));
+
+    // A second contract/hub pair can be deployed in the same env
+    let _other_hub = t.env.register(MockGameHub, ());
+}
@@ -5,7 +5,7 @@ use soroban_sdk::{contract, contractevent, contractimpl, Address, Env};
 /// Mock Game Hub contract for game studio development
 ///
 /// This contract provides the same external interface that games expect
-/// (start_game, end_game) but does nothing internally. It exists purely
+/// (start_game, end_game, end_game_detailed, end_game_draw, cancel_game) but does nothing internally. It exists purely
 /// for game contracts to compile and integrate during development.
 #[contract]
 pub struct MockGameHub;
@@ -26,6 +26,16 @@ pub struct GameEnded {
     pub player1_won: bool,
 }
 
+#[contractevent]
+pub struct GameDrawn {
+    pub session_id: u32,
+}
+
+#[contractevent]
+pub struct GameCancelled {
+    pub session_id: u32,
+}
+
 #[contractimpl]
 impl MockGameHub {
     /// Start a game session
@@ -77,6 +87,49 @@ impl MockGameHub {
         }
         .publish(&env);
     }
+
+    /// End a game session with its final scorecard
+    ///
+    /// # Arguments
+    /// * `session_id` - The game session being ended
+    /// * `player1_won` - True if player1 won, false if player2 won
+    /// * `p1_score`, `p2_score` - Runs scored by each player (ignored in mock)
+    /// * `target` - Runs the chasing side needed (ignored in mock)
+    /// * `innings_played` - Innings begun, counting super-over halves (ignored in mock)
+    pub fn end_game_detailed(
+        env: Env,
+        session_id: u32,
+        player1_won: bool,
+        _p1_score: u32,
+        _p2_score: u32,
+        _target: u32,
+        _innings_played: u32,
+    ) {
+        // No auth required for mock
+        GameEnded {
+            session_id,
+            player1_won,
+        }
+        .publish(&env);
+    }
+
+    /// End a game session with no winner
+    ///
+    /// # Arguments
+    /// * `session_id` - The game session being ended
+    pub fn end_game_draw(env: Env, session_id: u32) {
+        // No auth required for mock
+        GameDrawn { session_id }.publish(&env);
+    }
+
+    /// Cancel a game session; neither player wins and stakes go back
+    ///
+    /// # Arguments
+    /// * `session_id` - The game session being cancelled
+    pub fn cancel_game(env: Env, session_id: u32) {
+        // No auth required for mock
+        GameCancelled { session_id }.publish(&env);
+    }
 }
 
 #[cfg(test)]
@@ -93,5 +146,11 @@ mod test {
         let player2 = Address::generate(&env);
         client.start_game(&game_id, &1, &player1, &player2, &1000, &1000);
         client.end_game(&1, &true);
+        client.start_game(&game_id, &4, &player1, &player2, &1000, &1000);
+        client.end_game_detailed(&4, &false, &12, &13, &13, &2);
+        client.start_game(&game_id, &2, &player1, &player2, &1000, &1000);
+        client.end_game_draw(&2);
+        client.start_game(&game_id, &3, &player1, &player2, &1000, &1000);
+        client.cancel_game(&3);
     }
 }